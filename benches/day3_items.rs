@@ -0,0 +1,102 @@
+//! Compares the `HashSet<u8>` rucksack code day3 used to have with the
+//! `ItemSet` bitmask on a generated input. Run with `cargo bench`.
+
+use aoc2022::day3::items::ItemSet;
+use aoc2022::day3::{generate, priority};
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const GROUPS: usize = 100_000;
+const RUNS: usize = 10;
+
+fn hash_sets(input: &str) -> (u32, u32) {
+    let rucksacks: Vec<(HashSet<u8>, HashSet<u8>)> = input
+        .lines()
+        .map(|line| {
+            let items: Vec<u8> = line.chars().filter_map(priority).collect();
+            let (first, second) = items.split_at(items.len() / 2);
+            (
+                first.iter().copied().collect(),
+                second.iter().copied().collect(),
+            )
+        })
+        .collect();
+    let duplicates = rucksacks
+        .iter()
+        .filter_map(|(first, second)| first.intersection(second).next())
+        .map(|&p| p as u32)
+        .sum();
+    let badges = rucksacks
+        .chunks(3)
+        .filter_map(|group| {
+            let all: Vec<HashSet<u8>> = group
+                .iter()
+                .map(|(first, second)| first.union(second).copied().collect())
+                .collect();
+            all[0]
+                .iter()
+                .find(|p| all[1..].iter().all(|items| items.contains(p)))
+                .copied()
+        })
+        .map(u32::from)
+        .sum();
+    (duplicates, badges)
+}
+
+fn bitmasks(input: &str) -> (u32, u32) {
+    let rucksacks: Vec<(ItemSet, ItemSet)> = input
+        .lines()
+        .map(|line| {
+            let items: Vec<u8> = line.chars().filter_map(priority).collect();
+            let (first, second) = items.split_at(items.len() / 2);
+            (
+                first.iter().copied().collect(),
+                second.iter().copied().collect(),
+            )
+        })
+        .collect();
+    let duplicates = rucksacks
+        .iter()
+        .filter_map(|&(first, second)| (first & second).first())
+        .map(u32::from)
+        .sum();
+    let badges = rucksacks
+        .chunks(3)
+        .filter_map(|group| {
+            group
+                .iter()
+                .map(|&(first, second)| first | second)
+                .reduce(|acc, items| acc & items)?
+                .first()
+        })
+        .map(u32::from)
+        .sum();
+    (duplicates, badges)
+}
+
+fn median(input: &str, solve: fn(&str) -> (u32, u32)) -> (Duration, (u32, u32)) {
+    let mut samples = Vec::with_capacity(RUNS);
+    let mut answer = (0, 0);
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        answer = black_box(solve(black_box(input)));
+        samples.push(start.elapsed());
+    }
+    samples.sort();
+    (samples[RUNS / 2], answer)
+}
+
+fn main() {
+    let input = generate::rucksacks(GROUPS, 1);
+    let (hashed, expected) = median(&input, hash_sets);
+    let (masked, answer) = median(&input, bitmasks);
+    assert_eq!(answer, expected);
+    println!("{} rucksacks, median of {} runs", GROUPS * 3, RUNS);
+    println!("HashSet<u8>  {:>12.2?}", hashed);
+    println!("ItemSet      {:>12.2?}", masked);
+    println!(
+        "speedup      {:>11.1}x",
+        hashed.as_secs_f64() / masked.as_secs_f64()
+    );
+}
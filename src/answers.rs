@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::input::data_dir;
+use crate::registry::Part;
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    correct: HashMap<(u8, Part), String>,
+    rejected: HashMap<(u8, Part), Vec<String>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Rejected,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Rejected)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Rejected => write!(f, "FAIL (previously rejected guess)"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Answers {
+    /// Whether any answer, right or rejected, is recorded for `day`.
+    pub fn knows(&self, day: u8) -> bool {
+        self.correct
+            .keys()
+            .chain(self.rejected.keys())
+            .any(|&(known, _)| known == day)
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &str) -> Verdict {
+        let key = (day, part);
+        if self
+            .rejected
+            .get(&key)
+            .is_some_and(|guesses| guesses.iter().any(|guess| guess == answer))
+        {
+            return Verdict::Rejected;
+        }
+        match self.correct.get(&key) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = || {
+                format!(
+                    "answers line {}: expected 'day part answer', found {:?}",
+                    index + 1,
+                    line
+                )
+            };
+            let mut fields = line.splitn(3, ' ');
+            let day = fields.next().and_then(|day| day.parse::<u8>().ok());
+            let part = fields.next().and_then(|part| part.parse::<Part>().ok());
+            let answer = fields.next().map(str::trim);
+            let (day, part, answer) = match (day, part, answer) {
+                (Some(day), Some(part), Some(answer)) if !answer.is_empty() => (day, part, answer),
+                _ => return Err(malformed()),
+            };
+            match answer.strip_prefix('!') {
+                Some(guess) => answers
+                    .rejected
+                    .entry((day, part))
+                    .or_default()
+                    .push(guess.to_string()),
+                None => {
+                    if answers
+                        .correct
+                        .insert((day, part), answer.to_string())
+                        .is_some()
+                    {
+                        return Err(format!(
+                            "answers line {}: day {} part {} is listed twice",
+                            index + 1,
+                            day,
+                            part
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(answers)
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    data_dir().join("answers")
+}
+
+pub fn load(path: &Path) -> Result<Answers, String> {
+    fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+        .parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "# day part answer
+1 1 24000
+1 2 45000
+4 2 !621
+5 1 CMZ";
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::from_str(EXAMPLE).unwrap();
+        assert_eq!(answers.check(1, Part::One, "24000"), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, Part::Two, "41000"),
+            Verdict::Fail {
+                expected: "45000".to_string()
+            }
+        );
+        assert_eq!(answers.check(4, Part::Two, "621"), Verdict::Rejected);
+        assert_eq!(answers.check(4, Part::Two, "804"), Verdict::Unknown);
+        assert_eq!(answers.check(5, Part::One, "CMZ"), Verdict::Pass);
+        assert!(answers.knows(4));
+        assert!(!answers.knows(2));
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(Answers::from_str("1 3 24000").is_err());
+        assert!(Answers::from_str("1 1 24000\n1 1 24001").is_err());
+    }
+
+    #[test]
+    fn test_checked_in_answers() {
+        assert!(load(&default_path()).is_ok());
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::parse::{Mode, ParseError};
+use crate::registry::{Day, Part};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Parse,
+    Part(Part),
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+impl FromStr for Stage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parse" => Ok(Stage::Parse),
+            "part1" => Ok(Stage::Part(Part::One)),
+            "part2" => Ok(Stage::Part(Part::Two)),
+            _ => Err(format!("unknown stage {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+        Some(Stats {
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Measurement {
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+pub fn bench(day: &Day, input: &str, runs: usize) -> Result<Vec<Measurement>, ParseError> {
+    let mut samples: Vec<(Stage, Vec<Duration>)> = vec![
+        (Stage::Parse, Vec::with_capacity(runs)),
+        (Stage::Part(Part::One), Vec::with_capacity(runs)),
+        (Stage::Part(Part::Two), Vec::with_capacity(runs)),
+    ];
+    for _ in 0..runs {
+        let solved = day.solve(input, &Part::ALL, Mode::Lenient)?;
+        samples[0].1.push(solved.parse_elapsed());
+        for (answer, (_, durations)) in solved.answers().iter().zip(&mut samples[1..]) {
+            durations.push(answer.elapsed());
+        }
+    }
+    Ok(samples
+        .into_iter()
+        .flat_map(|(stage, durations)| {
+            Stats::from_samples(durations).map(|stats| Measurement {
+                day: day.number(),
+                stage,
+                stats,
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Default)]
+pub struct Baseline {
+    medians: HashMap<(u8, Stage), Duration>,
+}
+
+impl Baseline {
+    pub fn from_measurements(measurements: &[Measurement]) -> Self {
+        Baseline {
+            medians: measurements
+                .iter()
+                .map(|m| ((m.day, m.stage), m.stats.median))
+                .collect(),
+        }
+    }
+
+    pub fn change(&self, measurement: &Measurement) -> Option<f64> {
+        self.medians
+            .get(&(measurement.day, measurement.stage))
+            .filter(|before| !before.is_zero())
+            .map(|before| {
+                (measurement.stats.median.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut lines = self
+            .medians
+            .iter()
+            .map(|((day, stage), median)| format!("{} {} {}", day, stage, median.as_nanos()))
+            .collect::<Vec<_>>();
+        lines.sort();
+        lines.insert(0, "# day stage median-nanoseconds".to_string());
+        fs::write(path, lines.join("\n") + "\n")
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+            .parse()
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut medians = HashMap::new();
+        for (index, line) in s.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match fields[..] {
+                [day, stage, nanos] => day.parse().ok().and_then(|day| {
+                    Some((
+                        (day, stage.parse().ok()?),
+                        Duration::from_nanos(nanos.parse().ok()?),
+                    ))
+                }),
+                _ => None,
+            };
+            let (key, median) = parsed.ok_or_else(|| {
+                format!(
+                    "baseline line {}: expected 'day stage nanoseconds', found {:?}",
+                    index + 1,
+                    line
+                )
+            })?;
+            medians.insert(key, median);
+        }
+        Ok(Baseline { medians })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 3, 2, 4].map(Duration::from_millis).to_vec();
+        assert_eq!(
+            Stats::from_samples(samples),
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(5),
+            })
+        );
+        assert_eq!(Stats::from_samples(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_bench_and_baseline() {
+        let day = registry::get(6).unwrap();
+        let measurements = bench(day, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 3).unwrap();
+        assert_eq!(
+            measurements.iter().map(|m| m.stage).collect::<Vec<_>>(),
+            vec![Stage::Parse, Stage::Part(Part::One), Stage::Part(Part::Two)]
+        );
+        let baseline = Baseline::from_measurements(&measurements);
+        let text = "# day stage median-nanoseconds\n6 parse 100\n6 part1 200";
+        let parsed = Baseline::from_str(text).unwrap();
+        assert_eq!(
+            parsed.medians.get(&(6, Stage::Part(Part::One))),
+            Some(&Duration::from_nanos(200))
+        );
+        assert_eq!(baseline.medians.len(), 3);
+    }
+}
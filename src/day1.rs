@@ -1,29 +1,425 @@
-use itertools::Itertools;
-use std::cmp::Reverse;
-
-fn parse_food(data: &str) -> impl Iterator<Item = u32> + '_ {
-    data.split("\n\n").map(|elf| {
-        elf.split('\n')
-            .flat_map(|food| food.parse::<u32>().ok())
-            .sum()
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
+use std::borrow::Borrow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::mem;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    index: usize,
+    items: Vec<u32>,
+}
+
+impl Elf {
+    /// The elf's position in the input, counting from 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn items(&self) -> &[u32] {
+        &self.items
+    }
+
+    pub fn total(&self) -> u64 {
+        self.items.iter().map(|&calories| u64::from(calories)).sum()
+    }
+}
+
+pub struct Calories<R: BufRead> {
+    reader: R,
+    buffer: String,
+    line: usize,
+    index: usize,
+    items: Vec<u32>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Calories<R> {
+    pub fn new(reader: R) -> Self {
+        Calories {
+            reader,
+            buffer: String::new(),
+            line: 0,
+            index: 0,
+            items: Vec::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    fn finish_elf(&mut self) -> Elf {
+        let elf = Elf {
+            index: self.index,
+            items: mem::take(&mut self.items),
+        };
+        self.index += 1;
+        self.started = false;
+        elf
+    }
+}
+
+impl<R: BufRead> Iterator for Calories<R> {
+    type Item = Result<Elf, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            let read = match self.reader.read_line(&mut self.buffer) {
+                Ok(read) => read,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(ParseError::new(
+                        1,
+                        e.to_string(),
+                        "readable UTF-8 text",
+                    )
+                    .at_line(self.line + 1)));
+                }
+            };
+            if read == 0 {
+                self.done = true;
+                break;
+            }
+            self.line += 1;
+            let food = self.buffer.trim();
+            if food.is_empty() {
+                if self.started {
+                    return Some(Ok(self.finish_elf()));
+                }
+                continue;
+            }
+            // Only a valid item starts an elf, so lines that are all rejected
+            // do not leave behind an elf carrying nothing.
+            match food.parse::<u32>() {
+                Ok(calories) => {
+                    self.started = true;
+                    self.items.push(calories)
+                }
+                Err(_) => {
+                    return Some(Err(
+                        ParseError::new(1, food, "a calorie count").at_line(self.line)
+                    ))
+                }
+            }
+        }
+        if self.started {
+            Some(Ok(self.finish_elf()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Each elf's calorie total; bad lines fail or are skipped as `parser` decides.
+pub fn parse_food(data: &str, parser: &mut Parser) -> Result<Vec<u64>, ParseError> {
+    let inventory = Inventory::read(data.as_bytes(), parser)?;
+    Ok(inventory.elves.iter().map(Elf::total).collect())
+}
+
+struct Candidate<E>(E);
+
+impl<E: Borrow<Elf>> Candidate<E> {
+    fn key(&self) -> (Reverse<u64>, usize) {
+        let elf = self.0.borrow();
+        (Reverse(elf.total()), elf.index)
+    }
+}
+
+impl<E: Borrow<Elf>> PartialEq for Candidate<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<E: Borrow<Elf>> Eq for Candidate<E> {}
+
+impl<E: Borrow<Elf>> PartialOrd for Candidate<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E: Borrow<Elf>> Ord for Candidate<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TopElves<E> {
+    top: Vec<E>,
+    tied: Vec<E>,
+}
+
+impl<E: Borrow<Elf>> TopElves<E> {
+    /// The top elves, most calories first.
+    pub fn top(&self) -> &[E] {
+        &self.top
+    }
+
+    /// Elves left out that carry as much as the last of the top elves.
+    pub fn tied(&self) -> &[E] {
+        &self.tied
+    }
+
+    pub fn entries(&self) -> Vec<(usize, u64, &[u32])> {
+        self.top
+            .iter()
+            .map(|elf| {
+                let elf = elf.borrow();
+                (elf.index, elf.total(), elf.items.as_slice())
+            })
+            .collect()
+    }
+
+    pub fn total(&self) -> u64 {
+        self.top.iter().map(|elf| elf.borrow().total()).sum()
+    }
+}
+
+pub fn top_elves<E: Borrow<Elf>, I: IntoIterator<Item = E>>(elves: I, n: usize) -> TopElves<E> {
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    let mut tied: Vec<E> = Vec::new();
+    // The total every elf in `tied` shares. The cutoff only ever rises, so
+    // `tied` is only rebuilt when it does.
+    let mut tie = None;
+    for elf in elves {
+        heap.push(Candidate(elf));
+        if heap.len() <= n {
+            continue;
+        }
+        let Candidate(dropped) = heap.pop().unwrap();
+        let cutoff = heap.peek().map(|worst| worst.0.borrow().total());
+        if cutoff != tie {
+            tied.clear();
+            tie = cutoff;
+        }
+        if Some(dropped.borrow().total()) == cutoff {
+            tied.push(dropped);
+        }
+    }
+    tied.sort_by_key(|elf| elf.borrow().index);
+    TopElves {
+        top: heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|candidate| candidate.0)
+            .collect(),
+        tied,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    elves: usize,
+    empty: usize,
+    total: u64,
+    min: u64,
+    max: u64,
+    mean: f64,
+    median: f64,
+    std_dev: f64,
+    percentiles: Vec<(u8, f64)>,
+    item_counts: BTreeMap<usize, usize>,
+}
+
+impl Statistics {
+    pub fn elves(&self) -> usize {
+        self.elves
+    }
+
+    /// Elves carrying no items at all.
+    pub fn empty(&self) -> usize {
+        self.empty
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn median(&self) -> f64 {
+        self.median
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    pub fn percentiles(&self) -> &[(u8, f64)] {
+        &self.percentiles
+    }
+
+    /// How many elves carry each number of items.
+    pub fn item_counts(&self) -> &BTreeMap<usize, usize> {
+        &self.item_counts
+    }
+}
+
+const PERCENTILES: [u8; 5] = [10, 25, 75, 90, 99];
+
+fn percentile(sorted: &[u64], p: u8) -> f64 {
+    let rank = (sorted.len() - 1) as f64 * p as f64 / 100.0;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    let weight = rank - low as f64;
+    sorted[low] as f64 * (1.0 - weight) + sorted[high] as f64 * weight
+}
+
+pub fn statistics(elves: &[Elf]) -> Option<Statistics> {
+    let mut totals: Vec<u64> = elves.iter().map(Elf::total).collect();
+    totals.sort_unstable();
+    let (min, max) = (*totals.first()?, *totals.last()?);
+    let count = totals.len();
+    let total: u64 = totals.iter().sum();
+    let mean = total as f64 / count as f64;
+    let variance = totals
+        .iter()
+        .map(|&t| (t as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count as f64;
+    let mut item_counts = BTreeMap::new();
+    for elf in elves {
+        *item_counts.entry(elf.items.len()).or_insert(0) += 1;
+    }
+    Some(Statistics {
+        elves: count,
+        empty: elves.iter().filter(|elf| elf.items.is_empty()).count(),
+        total,
+        min,
+        max,
+        mean,
+        median: percentile(&totals, 50),
+        std_dev: variance.sqrt(),
+        percentiles: PERCENTILES
+            .iter()
+            .map(|&p| (p, percentile(&totals, p)))
+            .collect(),
+        item_counts,
     })
 }
 
-pub fn main() {
-    let food: Vec<u32> = parse_food(include_str!("data/day1")).collect();
-    println!("{}", food.iter().max().unwrap());
-    println!(
-        "{}",
-        food.iter()
-            .sorted_by_key(|i| Reverse(*i))
-            .take(3)
-            .sum::<u32>()
-    )
+impl Display for Statistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "elves: {} ({} empty)", self.elves, self.empty)?;
+        writeln!(f, "total: {}", self.total)?;
+        writeln!(f, "min: {}", self.min)?;
+        writeln!(f, "max: {}", self.max)?;
+        writeln!(f, "mean: {:.1}", self.mean)?;
+        writeln!(f, "median: {:.1}", self.median)?;
+        writeln!(f, "std dev: {:.1}", self.std_dev)?;
+        for (p, value) in &self.percentiles {
+            writeln!(f, "p{}: {:.1}", p, value)?;
+        }
+        write!(f, "elves by item count:")?;
+        for (items, elves) in &self.item_counts {
+            write!(f, " {}:{}", items, elves)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn histogram(elves: &[Elf], buckets: usize, width: usize) -> String {
+    let totals: Vec<u64> = elves.iter().map(Elf::total).collect();
+    let (min, max) = match (totals.iter().min(), totals.iter().max()) {
+        (Some(&min), Some(&max)) if buckets > 0 => (min, max),
+        _ => return String::new(),
+    };
+    let size = (max - min) / buckets as u64 + 1;
+    let mut counts = vec![0; buckets];
+    for total in totals {
+        counts[((total - min) / size) as usize] += 1;
+    }
+    let most = counts.iter().copied().max().unwrap_or(0).max(1);
+    counts
+        .iter()
+        .enumerate()
+        .map(|(bucket, &count)| {
+            let low = min + bucket as u64 * size;
+            format!(
+                "{:>7}-{:<7} {:<width$} {}",
+                low,
+                low + size - 1,
+                "#".repeat(count * width / most),
+                count,
+                width = width
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl Inventory {
+    pub fn read<R: BufRead>(reader: R, parser: &mut Parser) -> Result<Self, ParseError> {
+        let mut elves = Vec::new();
+        for elf in Calories::new(reader) {
+            match elf {
+                Ok(elf) => elves.push(elf),
+                Err(e) => parser.skip(e)?,
+            }
+        }
+        if elves.is_empty() {
+            return Err(parser.fail(ParseError::new(1, "", "at least one elf").at_line(1)));
+        }
+        Ok(Inventory { elves })
+    }
+
+    pub fn elves(&self) -> &[Elf] {
+        &self.elves
+    }
+}
+
+impl Solution for Inventory {
+    const DAY: u8 = 1;
+
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        Inventory::read(input.as_bytes(), parser)
+    }
+
+    fn part1(&self) -> u64 {
+        self.elves
+            .iter()
+            .map(Elf::total)
+            .max()
+            .expect("an inventory has at least one elf")
+    }
+
+    fn part2(&self) -> u64 {
+        top_elves(&self.elves, 3).total()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
+
+    fn parse_elves(input: &str) -> Vec<Elf> {
+        Inventory::parse(input, &mut Parser::strict(1))
+            .unwrap()
+            .elves
+    }
 
     const EXAMPLE: &str = "1000
 2000
@@ -42,6 +438,148 @@ mod tests {
 
     #[test]
     fn test_max() {
-        assert_eq!(parse_food(EXAMPLE).max().unwrap(), 24000);
+        let totals = parse_food(EXAMPLE, &mut Parser::strict(1)).unwrap();
+        assert_eq!(totals.into_iter().max().unwrap(), 24000);
+    }
+
+    #[test]
+    fn test_top_three() {
+        assert_eq!(
+            Inventory::parse(EXAMPLE, &mut Parser::strict(1))
+                .unwrap()
+                .part2(),
+            45000
+        );
+    }
+
+    #[test]
+    fn test_top_elves() {
+        let inventory = Inventory::parse(EXAMPLE, &mut Parser::strict(1)).unwrap();
+        let top = top_elves(inventory.elves(), 2);
+        assert_eq!(
+            top.entries(),
+            vec![
+                (3, 24000, [7000, 8000, 9000].as_slice()),
+                (2, 11000, [5000, 6000].as_slice())
+            ]
+        );
+        assert!(top.tied.is_empty());
+        assert!(top_elves(inventory.elves(), 0).top.is_empty());
+        assert_eq!(top_elves(inventory.elves(), 9).top.len(), 5);
+    }
+
+    #[test]
+    fn test_top_elves_ties() {
+        let input = "5\n\n7\n\n5\n\n9\n\n5";
+        let top = top_elves(parse_elves(input), 2);
+        assert_eq!(
+            top.entries(),
+            vec![(3, 9, [9].as_slice()), (1, 7, [7].as_slice())]
+        );
+        assert!(top.tied.is_empty());
+        let top = top_elves(parse_elves(input), 3);
+        assert_eq!(top.entries()[2], (0, 5, [5].as_slice()));
+        assert_eq!(
+            top.tied.iter().map(|elf| elf.index).collect_vec(),
+            vec![2, 4]
+        );
+        let top = top_elves(parse_elves("1\n\n1\n\n5\n\n5\n\n5"), 2);
+        assert_eq!(top.total(), 10);
+        assert_eq!(top.tied.len(), 1);
+        assert_eq!(top.tied[0].total(), 5);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let stats = statistics(&parse_elves(EXAMPLE)).unwrap();
+        assert_eq!(stats.elves, 5);
+        assert_eq!(stats.empty, 0);
+        assert_eq!(stats.total, 55000);
+        assert_eq!((stats.min, stats.max), (4000, 24000));
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert!((stats.std_dev - 6985.7).abs() < 0.1);
+        assert_eq!(stats.percentiles[1], (25, 6000.0));
+        assert_eq!(
+            stats.item_counts.into_iter().collect_vec(),
+            vec![(1, 2), (2, 1), (3, 2)]
+        );
+        assert_eq!(statistics(&[]), None);
+    }
+
+    #[test]
+    fn test_histogram() {
+        assert_eq!(
+            histogram(&parse_elves(EXAMPLE), 2, 4),
+            "   4000-14000   #### 4\n  14001-24001   #    1"
+        );
+        assert_eq!(histogram(&[], 2, 4), "");
+    }
+
+    #[test]
+    fn test_crlf_and_blank_lines() {
+        let input = "\r\n1000\r\n2000  \r\n\r\n\r\n\r\n3000\r\n\r\n";
+        let totals = parse_food(input, &mut Parser::strict(1)).unwrap();
+        assert_eq!(totals, vec![3000, 3000]);
+    }
+
+    #[test]
+    fn test_bad_line() {
+        let input = "1000\n2OOO\n\n3000";
+        let elves = Calories::new(input.as_bytes()).collect_vec();
+        assert_eq!(
+            elves,
+            vec![
+                Err(ParseError::new(1, "2OOO", "a calorie count").at_line(2)),
+                Ok(Elf {
+                    index: 0,
+                    items: vec![1000]
+                }),
+                Ok(Elf {
+                    index: 1,
+                    items: vec![3000]
+                }),
+            ]
+        );
+        assert!(Inventory::parse(input, &mut Parser::strict(1)).is_err());
+        assert!(parse_food(input, &mut Parser::strict(1)).is_err());
+        let mut parser = Parser::new(1, crate::parse::Mode::Lenient);
+        assert_eq!(parse_food(input, &mut parser).unwrap(), vec![1000, 3000]);
+        assert_eq!(parser.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_rejected_elf() {
+        let mut parser = Parser::new(1, crate::parse::Mode::Lenient);
+        let inventory = Inventory::parse("abc\n\n1000\n\nx\ny\n", &mut parser).unwrap();
+        assert_eq!(parser.warnings().len(), 3);
+        assert_eq!(
+            inventory.elves(),
+            [Elf {
+                index: 0,
+                items: vec![1000]
+            }]
+        );
+        let stats = statistics(inventory.elves()).unwrap();
+        assert_eq!((stats.elves, stats.empty, stats.min), (1, 0, 1000));
+    }
+
+    #[test]
+    fn test_no_elves() {
+        assert!(Inventory::parse("", &mut Parser::strict(1)).is_err());
+        let mut parser = Parser::new(1, crate::parse::Mode::Lenient);
+        let error = Inventory::parse("\n\n", &mut parser).err().unwrap();
+        assert_eq!(
+            (error.day, error.expected.as_str()),
+            (1, "at least one elf")
+        );
+    }
+
+    #[test]
+    fn test_large_totals() {
+        let input = format!("{}\n{}\n\n1", u32::MAX, u32::MAX);
+        let inventory = Inventory::parse(&input, &mut Parser::strict(1)).unwrap();
+        assert_eq!(inventory.part1(), 2 * u32::MAX as u64);
+        assert_eq!(inventory.part2(), 2 * u32::MAX as u64 + 1);
     }
 }
@@ -1,161 +1,139 @@
-use std::str::FromStr;
-
-enum Outcome {
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
+use rules::{Ruleset, Shape};
+use std::fmt::{Display, Formatter};
+
+pub mod infer;
+pub mod optimize;
+pub mod rules;
+pub mod tournament;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Outcome {
     Win,
     Draw,
     Loss,
 }
 
 impl Outcome {
-    fn score(&self) -> u32 {
-        match self {
-            Outcome::Win => 6,
-            Outcome::Draw => 3,
-            Outcome::Loss => 0,
-        }
-    }
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
 }
 
-impl FromStr for Outcome {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "X" => Ok(Outcome::Loss),
-            "Y" => Ok(Outcome::Draw),
-            "Z" => Ok(Outcome::Win),
-            _ => Err(()),
-        }
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Outcome::Win => "win",
+            Outcome::Draw => "draw",
+            Outcome::Loss => "loss",
+        })
     }
 }
 
-#[derive(Clone)]
-enum RPS {
-    Rock,
-    Paper,
-    Scissors,
+fn split_round(s: &str) -> Result<(&str, &str), ParseError> {
+    s.split_once(' ')
+        .ok_or_else(|| ParseError::new(1, s, "two columns separated by a space"))
 }
 
-impl RPS {
-    fn score(&self) -> u32 {
-        match self {
-            RPS::Rock => 1,
-            RPS::Paper => 2,
-            RPS::Scissors => 3,
-        }
-    }
-    fn play(&self, against: &RPS) -> Outcome {
-        match (self, against) {
-            (RPS::Rock, RPS::Rock) | (RPS::Paper, RPS::Paper) | (RPS::Scissors, RPS::Scissors) => {
-                Outcome::Draw
-            }
-            (RPS::Rock, RPS::Scissors) | (RPS::Scissors, RPS::Paper) | (RPS::Paper, RPS::Rock) => {
-                Outcome::Win
-            }
-            _ => Outcome::Loss,
-        }
-    }
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Match {
+    opponent: Shape,
+    you: Shape,
 }
 
-impl FromStr for RPS {
-    type Err = ();
+impl Match {
+    pub fn new(opponent: Shape, you: Shape) -> Self {
+        Match { opponent, you }
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" | "X" => Ok(RPS::Rock),
-            "B" | "Y" => Ok(RPS::Paper),
-            "C" | "Z" => Ok(RPS::Scissors),
-            _ => Err(()),
-        }
+    pub fn opponent(&self) -> Shape {
+        self.opponent
+    }
+
+    pub fn you(&self) -> Shape {
+        self.you
     }
 }
 
-struct Match {
-    you: RPS,
-    opponent: RPS,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Strategy {
+    opponent: Shape,
+    outcome: Outcome,
 }
 
-impl Match {
-    fn play(&self) -> Outcome {
-        self.you.play(&self.opponent)
+impl Strategy {
+    pub fn new(opponent: Shape, outcome: Outcome) -> Self {
+        Strategy { opponent, outcome }
     }
 
-    fn score(&self) -> u32 {
-        self.play().score() + self.you.score()
+    pub fn opponent(&self) -> Shape {
+        self.opponent
     }
-}
 
-impl FromStr for Match {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (opp, me) = s.split_once(' ').ok_or(())?;
-        Ok(Match {
-            you: RPS::from_str(me)?,
-            opponent: RPS::from_str(opp)?,
-        })
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
     }
 }
 
-struct Strategy {
-    opponent: RPS,
-    outcome: Outcome,
-}
-
-impl Strategy {
-    fn solve(&self) -> RPS {
-        match self.outcome {
-            Outcome::Win => match self.opponent {
-                RPS::Rock => RPS::Paper,
-                RPS::Paper => RPS::Scissors,
-                RPS::Scissors => RPS::Rock,
-            },
-            Outcome::Draw => self.opponent.clone(),
-            Outcome::Loss => match self.opponent {
-                RPS::Rock => RPS::Scissors,
-                RPS::Paper => RPS::Rock,
-                RPS::Scissors => RPS::Paper,
-            },
+pub struct Guide {
+    rules: Ruleset,
+    matches: Vec<Match>,
+    strategies: Vec<Strategy>,
+}
+
+impl Guide {
+    /// Reads every line both as a match and as a strategy under `rules`. A
+    /// line only needs to be valid in one reading to count for that part, and
+    /// a line that fails both readings is reported once.
+    pub fn with_rules(
+        rules: Ruleset,
+        input: &str,
+        parser: &mut Parser,
+    ) -> Result<Self, ParseError> {
+        let matches = rules.matches(input, parser)?;
+        let mut outcomes = Parser::new(<Guide as Solution>::DAY, parser.mode());
+        let strategies = rules.strategies(input, &mut outcomes)?;
+        for warning in outcomes.into_warnings() {
+            if !parser.warnings().iter().any(|w| w.line == warning.line) {
+                parser.skip(warning)?;
+            }
         }
+        Ok(Guide {
+            rules,
+            matches,
+            strategies,
+        })
     }
 
-    fn score(&self) -> u32 {
-        self.outcome.score() + self.solve().score()
+    pub fn rules(&self) -> &Ruleset {
+        &self.rules
     }
-}
 
-impl FromStr for Strategy {
-    type Err = ();
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (opp, out) = s.split_once(' ').ok_or(())?;
-        Ok(Strategy {
-            outcome: Outcome::from_str(out)?,
-            opponent: RPS::from_str(opp)?,
-        })
+    pub fn strategies(&self) -> &[Strategy] {
+        &self.strategies
     }
 }
 
-fn score(input: &str) -> u32 {
-    input
-        .lines()
-        .flat_map(|line| Match::from_str(line).ok())
-        .map(|m| m.score())
-        .sum::<u32>()
-}
+impl Solution for Guide {
+    const DAY: u8 = 2;
 
-fn solve(input: &str) -> u32 {
-    input
-        .lines()
-        .flat_map(|line| Strategy::from_str(line).ok())
-        .map(|m| m.score())
-        .sum::<u32>()
-}
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        Guide::with_rules(Ruleset::default(), input, parser)
+    }
+
+    fn part1(&self) -> u32 {
+        self.rules.score_matches(&self.matches)
+    }
 
-pub fn main() {
-    let input = include_str!("data/day1");
-    println!("{}", score(input));
-    println!("{}", solve(input));
+    fn part2(&self) -> u32 {
+        self.rules.score_strategies(&self.strategies)
+    }
 }
 
 #[cfg(test)]
@@ -168,11 +146,35 @@ C Z";
 
     #[test]
     fn test_score() {
-        assert_eq!(15, score(EXAMPLE));
+        assert_eq!(
+            15,
+            Guide::parse(EXAMPLE, &mut Parser::strict(2))
+                .unwrap()
+                .part1()
+        );
     }
 
     #[test]
     fn test_solution() {
-        assert_eq!(12, solve(EXAMPLE));
+        assert_eq!(
+            12,
+            Guide::parse(EXAMPLE, &mut Parser::strict(2))
+                .unwrap()
+                .part2()
+        );
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let rules: Ruleset = include_str!("data/rules/rpsls").parse().unwrap();
+        let mut parser = Parser::new(2, crate::parse::Mode::Lenient);
+        let guide = Guide::with_rules(rules, "A V\nB W\nE Z\nA Q", &mut parser).unwrap();
+        assert_eq!(guide.matches().len(), 3);
+        assert_eq!(guide.part1(), 8 + 4 + 5);
+        assert_eq!(guide.strategies().len(), 1);
+        assert_eq!(guide.part2(), 6 + 4);
+        let mut lines: Vec<usize> = parser.warnings().iter().map(|w| w.line).collect();
+        lines.sort();
+        assert_eq!(lines, [1, 2, 4]);
     }
 }
@@ -0,0 +1,190 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use super::rules::{Ruleset, Shape};
+use super::Outcome;
+use crate::parse::{ParseError, Parser};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Reading {
+    Shape(Shape),
+    Outcome(Outcome),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    letters: Vec<(String, Reading)>,
+    total: u32,
+}
+
+impl Mapping {
+    pub fn letters(&self) -> &[(String, Reading)] {
+        &self.letters
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    pub fn describe(&self, rules: &Ruleset) -> String {
+        self.letters
+            .iter()
+            .map(|(letter, reading)| {
+                let meaning = match reading {
+                    Reading::Shape(shape) => rules.shape(*shape).name().to_string(),
+                    Reading::Outcome(outcome) => outcome.to_string(),
+                };
+                format!("{}={}", letter, meaning)
+            })
+            .join(" ")
+    }
+}
+
+/// Every one-to-one reading of the second column, best total first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inference {
+    shapes: Vec<Mapping>,
+    outcomes: Vec<Mapping>,
+}
+
+impl Inference {
+    pub fn shapes(&self) -> &[Mapping] {
+        &self.shapes
+    }
+
+    pub fn outcomes(&self) -> &[Mapping] {
+        &self.outcomes
+    }
+
+    pub fn best_shapes(&self) -> Option<&Mapping> {
+        self.shapes.first()
+    }
+
+    pub fn worst_shapes(&self) -> Option<&Mapping> {
+        self.shapes.last()
+    }
+
+    pub fn best_outcomes(&self) -> Option<&Mapping> {
+        self.outcomes.first()
+    }
+
+    pub fn worst_outcomes(&self) -> Option<&Mapping> {
+        self.outcomes.last()
+    }
+}
+
+fn tally<'a>(
+    rules: &Ruleset,
+    input: &'a str,
+    parser: &mut Parser,
+) -> Result<BTreeMap<(Shape, &'a str), u32>, ParseError> {
+    let mut rounds = BTreeMap::new();
+    for (index, line) in input.lines().enumerate() {
+        match super::split_round(line)
+            .and_then(|(opp, letter)| Ok((rules.opponent_shape(opp)?, letter)))
+        {
+            Ok(round) => *rounds.entry(round).or_insert(0) += 1,
+            Err(e) => parser.skip(e.at_line(index + 1))?,
+        }
+    }
+    Ok(rounds)
+}
+
+fn mappings<T: Copy>(
+    letters: &[&str],
+    meanings: impl Iterator<Item = T>,
+    reading: impl Fn(T) -> Reading,
+    total: impl Fn(&BTreeMap<&str, T>) -> Option<u32>,
+) -> Vec<Mapping> {
+    let mut mappings: Vec<Mapping> = meanings
+        .permutations(letters.len())
+        .filter_map(|assigned| {
+            let lookup: BTreeMap<&str, T> = letters.iter().copied().zip(assigned).collect();
+            Some(Mapping {
+                total: total(&lookup)?,
+                letters: lookup
+                    .iter()
+                    .map(|(letter, &meaning)| (letter.to_string(), reading(meaning)))
+                    .collect(),
+            })
+        })
+        .collect();
+    mappings.sort_by_key(|mapping| Reverse(mapping.total));
+    mappings
+}
+
+/// Scores the guide under every assignment of its second-column letters to
+/// distinct shapes and to distinct outcomes.
+pub fn infer(rules: &Ruleset, input: &str, parser: &mut Parser) -> Result<Inference, ParseError> {
+    let rounds = tally(rules, input, parser)?;
+    let letters: Vec<&str> = rounds
+        .keys()
+        .map(|&(_, letter)| letter)
+        .sorted()
+        .dedup()
+        .collect();
+    let shapes = mappings(&letters, rules.shapes(), Reading::Shape, |lookup| {
+        Some(
+            rounds
+                .iter()
+                .map(|(&(opponent, letter), count)| count * rules.score(lookup[letter], opponent))
+                .sum(),
+        )
+    });
+    let outcomes = mappings(
+        &letters,
+        Outcome::ALL.into_iter(),
+        Reading::Outcome,
+        |lookup| {
+            rounds
+                .iter()
+                .map(|(&(opponent, letter), count)| {
+                    let you = rules.respond(opponent, lookup[letter])?;
+                    Some(count * rules.score(you, opponent))
+                })
+                .sum()
+        },
+    );
+    Ok(Inference { shapes, outcomes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn test_infer() {
+        let rules = Ruleset::default();
+        let inference = infer(&rules, EXAMPLE, &mut Parser::strict(2)).unwrap();
+        assert_eq!(inference.shapes.len(), 6);
+        assert_eq!(inference.outcomes.len(), 6);
+
+        let best = inference.best_shapes().unwrap();
+        assert_eq!(best.total, 24);
+        assert_eq!(best.describe(&rules), "X=Scissors Y=Paper Z=Rock");
+        let worst = inference.worst_shapes().unwrap();
+        assert_eq!(worst.total, 6);
+        assert_eq!(worst.describe(&rules), "X=Rock Y=Scissors Z=Paper");
+
+        let best = inference.best_outcomes().unwrap();
+        assert_eq!(best.total, 18);
+        assert_eq!(best.describe(&rules), "X=win Y=loss Z=draw");
+        let worst = inference.worst_outcomes().unwrap();
+        assert_eq!(worst.total, 12);
+        assert_eq!(worst.describe(&rules), "X=loss Y=draw Z=win");
+    }
+
+    #[test]
+    fn test_infer_too_many_letters() {
+        let rules = Ruleset::default();
+        let inference = infer(&rules, "A W\nA X\nA Y\nA Z", &mut Parser::strict(2)).unwrap();
+        assert!(inference.shapes.is_empty());
+        assert!(inference.outcomes.is_empty());
+    }
+}
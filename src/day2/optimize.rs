@@ -0,0 +1,378 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use super::rules::{Ruleset, Shape};
+use super::{Outcome, Strategy};
+
+pub fn outcome_name(s: &str) -> Result<Outcome, String> {
+    match s {
+        "win" | "wins" => Ok(Outcome::Win),
+        "draw" | "draws" => Ok(Outcome::Draw),
+        "loss" | "losses" => Ok(Outcome::Loss),
+        _ => Err(format!("expected win, draw or loss, found {:?}", s)),
+    }
+}
+
+/// At most `percent` of the rounds may end in `outcome`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Limit {
+    pub outcome: Outcome,
+    pub percent: f64,
+}
+
+impl Limit {
+    fn cap(&self, rounds: usize) -> usize {
+        (rounds as f64 * self.percent / 100.0).floor() as usize
+    }
+}
+
+impl FromStr for Limit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (outcome, percent) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected OUTCOME=PERCENT, found {:?}", s))?;
+        let percent: f64 = percent
+            .trim_end_matches('%')
+            .parse()
+            .map_err(|_| format!("expected a percentage, found {:?}", percent))?;
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(format!(
+                "expected a percentage from 0 to 100, found {}",
+                percent
+            ));
+        }
+        Ok(Limit {
+            outcome: outcome_name(outcome)?,
+            percent,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal {
+    /// The strategy has to score strictly more than this.
+    pub target: u32,
+    pub minimise: Outcome,
+    pub limits: Vec<Limit>,
+}
+
+fn outcome_index(outcome: Outcome) -> usize {
+    match outcome {
+        Outcome::Loss => 0,
+        Outcome::Draw => 1,
+        Outcome::Win => 2,
+    }
+}
+
+struct Edge {
+    to: usize,
+    capacity: usize,
+    cost: i64,
+}
+
+/// Min-cost flow from the opponent's shapes to the outcomes we pick for them.
+///
+/// Node 0 is the source, then one node per shape, one per outcome and the sink.
+struct Network {
+    shapes: usize,
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+const SOURCE: usize = 0;
+
+impl Network {
+    fn new(shapes: usize) -> Self {
+        Network {
+            shapes,
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); shapes + Outcome::ALL.len() + 2],
+        }
+    }
+
+    fn shape_node(&self, shape: usize) -> usize {
+        1 + shape
+    }
+
+    fn outcome_node(&self, outcome: usize) -> usize {
+        1 + self.shapes + outcome
+    }
+
+    fn sink(&self) -> usize {
+        self.adjacency.len() - 1
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, capacity: usize, cost: i64) -> usize {
+        let index = self.edges.len();
+        self.edges.push(Edge { to, capacity, cost });
+        self.edges.push(Edge {
+            to: from,
+            capacity: 0,
+            cost: -cost,
+        });
+        self.adjacency[from].push(index);
+        self.adjacency[to].push(index + 1);
+        index
+    }
+
+    fn shortest_path(&self) -> Option<Vec<usize>> {
+        let mut distance = vec![i64::MAX; self.adjacency.len()];
+        let mut via = vec![None; self.adjacency.len()];
+        distance[SOURCE] = 0;
+        for _ in 0..self.adjacency.len() {
+            for (node, edges) in self.adjacency.iter().enumerate() {
+                if distance[node] == i64::MAX {
+                    continue;
+                }
+                for &index in edges {
+                    let edge = &self.edges[index];
+                    if edge.capacity > 0 && distance[node] + edge.cost < distance[edge.to] {
+                        distance[edge.to] = distance[node] + edge.cost;
+                        via[edge.to] = Some(index);
+                    }
+                }
+            }
+        }
+        let mut path = Vec::new();
+        let mut node = self.sink();
+        while node != SOURCE {
+            let index = via[node]?;
+            path.push(index);
+            node = self.edges[index ^ 1].to;
+        }
+        Some(path)
+    }
+
+    fn run(&mut self) -> usize {
+        let mut flow = 0;
+        while let Some(path) = self.shortest_path() {
+            let amount = path
+                .iter()
+                .map(|&index| self.edges[index].capacity)
+                .min()
+                .unwrap_or(0);
+            for &index in &path {
+                self.edges[index].capacity -= amount;
+                self.edges[index ^ 1].capacity += amount;
+            }
+            flow += amount;
+        }
+        flow
+    }
+}
+
+fn strategy(opponent: usize, outcome: usize) -> Strategy {
+    Strategy {
+        opponent: Shape(opponent),
+        outcome: Outcome::ALL[outcome],
+    }
+}
+
+/// The score of playing for `outcome` against `opponent`, or `None` if no
+/// shape reaches that outcome under the rules.
+fn strategy_score(rules: &Ruleset, opponent: usize, outcome: usize) -> Option<u32> {
+    rules
+        .resolve(&strategy(opponent, outcome))
+        .map(|game| rules.match_score(&game))
+}
+
+/// The highest scoring split of each opponent shape over the outcomes that
+/// respects `caps`, or `None` if the caps leave some round without an outcome.
+fn best_split(
+    rules: &Ruleset,
+    counts: &[usize],
+    caps: &[usize; 3],
+) -> Option<(u32, Vec<[usize; 3]>)> {
+    let rounds: usize = counts.iter().sum();
+    let mut network = Network::new(counts.len());
+    let mut choices = Vec::new();
+    for (shape, &count) in counts.iter().enumerate() {
+        network.add_edge(SOURCE, network.shape_node(shape), count, 0);
+        for outcome in 0..3 {
+            if let Some(score) = strategy_score(rules, shape, outcome) {
+                let (from, to) = (network.shape_node(shape), network.outcome_node(outcome));
+                let edge = network.add_edge(from, to, rounds, -(score as i64));
+                choices.push((shape, outcome, score, edge));
+            }
+        }
+    }
+    for (outcome, &cap) in caps.iter().enumerate() {
+        network.add_edge(network.outcome_node(outcome), network.sink(), cap, 0);
+    }
+    if network.run() < rounds {
+        return None;
+    }
+    let mut split = vec![[0; 3]; counts.len()];
+    let mut total = 0;
+    for (shape, outcome, score, edge) in choices {
+        let used = network.edges[edge ^ 1].capacity;
+        split[shape][outcome] = used;
+        total += used as u32 * score;
+    }
+    Some((total, split))
+}
+
+/// Finds a strategy that scores more than the target with as few rounds of
+/// the minimised outcome as possible, within the given limits.
+pub fn optimize(
+    rules: &Ruleset,
+    opponents: &[Shape],
+    goal: &Goal,
+) -> Result<Vec<Strategy>, String> {
+    let mut counts = vec![0; rules.shapes().count()];
+    for opponent in opponents {
+        counts[opponent.0] += 1;
+    }
+    let mut caps = [opponents.len(); 3];
+    for limit in &goal.limits {
+        let cap = &mut caps[outcome_index(limit.outcome)];
+        *cap = (*cap).min(limit.cap(opponents.len()));
+    }
+    let minimised = outcome_index(goal.minimise);
+    let split_with = |count: usize| {
+        let mut caps = caps;
+        caps[minimised] = count;
+        best_split(rules, &counts, &caps)
+    };
+    let beats = |split: &Option<(u32, _)>| {
+        split
+            .as_ref()
+            .is_some_and(|(score, _)| *score > goal.target)
+    };
+
+    let best = split_with(caps[minimised]);
+    if !beats(&best) {
+        return Err(match best {
+            Some((score, _)) => format!(
+                "the best strategy within the limits scores {}, which does not beat {}",
+                score, goal.target
+            ),
+            None => "the limits leave some rounds without an allowed outcome".to_string(),
+        });
+    }
+    let (mut low, mut high) = (0, caps[minimised]);
+    while low < high {
+        let middle = (low + high) / 2;
+        if beats(&split_with(middle)) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    let (_, mut split) = split_with(low).expect("the search only narrows to feasible caps");
+    Ok(opponents
+        .iter()
+        .map(|opponent| {
+            let shape = opponent.0;
+            let outcome = (0..3)
+                .find(|&outcome| split[shape][outcome] > 0)
+                .expect("every round has an outcome");
+            split[shape][outcome] -= 1;
+            strategy(shape, outcome)
+        })
+        .collect())
+}
+
+pub fn write_strategies<W: Write>(
+    rules: &Ruleset,
+    strategies: &[Strategy],
+    mut writer: W,
+) -> io::Result<()> {
+    for strategy in strategies {
+        writeln!(writer, "{}", rules.encode(strategy))?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2::Guide;
+    use crate::parse::Parser;
+    use crate::solution::Solution;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    fn solve(strategies: &[Strategy]) -> u32 {
+        Ruleset::default().score_strategies(strategies)
+    }
+
+    fn opponents(input: &str) -> Vec<Shape> {
+        Guide::parse(input, &mut Parser::strict(2))
+            .unwrap()
+            .strategies()
+            .iter()
+            .map(|strategy| strategy.opponent)
+            .collect()
+    }
+
+    fn count(strategies: &[Strategy], outcome: Outcome) -> usize {
+        strategies.iter().filter(|s| s.outcome == outcome).count()
+    }
+
+    #[test]
+    fn test_fewest_wins() {
+        let goal = Goal {
+            target: 15,
+            minimise: Outcome::Win,
+            limits: Vec::new(),
+        };
+        let strategies = optimize(&Ruleset::default(), &opponents(EXAMPLE), &goal).unwrap();
+        assert_eq!(count(&strategies, Outcome::Win), 1);
+        assert_eq!(solve(&strategies), 19);
+    }
+
+    #[test]
+    fn test_limits() {
+        let limit = Limit::from_str("win=34%").unwrap();
+        let mut goal = Goal {
+            target: 18,
+            minimise: Outcome::Loss,
+            limits: vec![limit],
+        };
+        let strategies = optimize(&Ruleset::default(), &opponents(EXAMPLE), &goal).unwrap();
+        assert_eq!(count(&strategies, Outcome::Loss), 0);
+        assert_eq!(count(&strategies, Outcome::Win), 1);
+        goal.target = 19;
+        assert!(optimize(&Ruleset::default(), &opponents(EXAMPLE), &goal).is_err());
+        assert!(Limit::from_str("wins=120").is_err());
+        assert!(Limit::from_str("ties=10%").is_err());
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let rules: Ruleset = include_str!("../data/rules/rpsls").parse().unwrap();
+        let goal = Goal {
+            target: 31,
+            minimise: Outcome::Loss,
+            limits: Vec::new(),
+        };
+        let opponents = opponents(EXAMPLE);
+        let strategies = optimize(&rules, &opponents, &goal).unwrap();
+        assert_eq!(count(&strategies, Outcome::Win), 3);
+        assert_eq!(rules.score_strategies(&strategies), 11 + 10 + 11);
+        assert!(optimize(&Ruleset::default(), &opponents, &goal).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = include_str!("../data/day2");
+        let goal = Goal {
+            target: 14979,
+            minimise: Outcome::Draw,
+            limits: vec![Limit::from_str("win=30%").unwrap()],
+        };
+        let strategies = optimize(&Ruleset::default(), &opponents(input), &goal).unwrap();
+        assert!(count(&strategies, Outcome::Win) * 100 <= strategies.len() * 30);
+        let mut file = Vec::new();
+        write_strategies(&Ruleset::default(), &strategies, &mut file).unwrap();
+        let guide =
+            Guide::parse(&String::from_utf8(file).unwrap(), &mut Parser::strict(2)).unwrap();
+        assert_eq!(guide.part2(), solve(&strategies));
+        assert!(guide.part2() > goal.target);
+    }
+}
@@ -0,0 +1,408 @@
+use std::str::FromStr;
+
+use super::{Match, Outcome, Strategy};
+use crate::parse::{ParseError, Parser};
+
+pub const DEFAULT_RULES: &str = include_str!("../data/rules/rps");
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Shape(pub(super) usize);
+
+impl Shape {
+    /// The shape's position in the rules file, counting from 0.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeRule {
+    name: String,
+    score: u32,
+    opponent: String,
+    player: String,
+}
+
+impl ShapeRule {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// The letter for this shape in the first column.
+    pub fn opponent(&self) -> &str {
+        &self.opponent
+    }
+
+    /// The letter for this shape when the second column is your shape.
+    pub fn player(&self) -> &str {
+        &self.player
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutcomeRule {
+    score: u32,
+    letter: String,
+}
+
+impl OutcomeRule {
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn letter(&self) -> &str {
+        &self.letter
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    shapes: Vec<ShapeRule>,
+    beats: Vec<Vec<bool>>,
+    outcomes: [OutcomeRule; 3],
+}
+
+fn outcome_index(outcome: Outcome) -> usize {
+    match outcome {
+        Outcome::Loss => 0,
+        Outcome::Draw => 1,
+        Outcome::Win => 2,
+    }
+}
+
+impl Ruleset {
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn shape(&self, shape: Shape) -> &ShapeRule {
+        &self.shapes[shape.0]
+    }
+
+    pub fn outcome(&self, outcome: Outcome) -> &OutcomeRule {
+        &self.outcomes[outcome_index(outcome)]
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> u32 {
+        self.shape(shape).score
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcome(outcome).score
+    }
+
+    pub fn play(&self, you: Shape, opponent: Shape) -> Outcome {
+        if self.beats[you.0][opponent.0] {
+            Outcome::Win
+        } else if self.beats[opponent.0][you.0] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn score(&self, you: Shape, opponent: Shape) -> u32 {
+        self.shape_score(you) + self.outcome_score(self.play(you, opponent))
+    }
+
+    /// Picks the highest scoring shape that gives `outcome` against `opponent`.
+    pub fn respond(&self, opponent: Shape, outcome: Outcome) -> Option<Shape> {
+        self.shapes()
+            .filter(|&you| self.play(you, opponent) == outcome)
+            .max_by_key(|&you| (self.shape_score(you), you == opponent))
+    }
+
+    fn find_shape(&self, column: &str, letter: impl Fn(&ShapeRule) -> &str) -> Option<Shape> {
+        self.shapes()
+            .find(|&shape| letter(self.shape(shape)) == column)
+    }
+
+    pub fn opponent_shape(&self, s: &str) -> Result<Shape, ParseError> {
+        self.find_shape(s, |rule| &rule.opponent)
+            .ok_or_else(|| ParseError::new(1, s, "an opponent shape letter"))
+    }
+
+    pub fn player_shape(&self, s: &str) -> Result<Shape, ParseError> {
+        self.find_shape(s, |rule| &rule.player)
+            .ok_or_else(|| ParseError::new(1, s, "a player shape letter"))
+    }
+
+    pub fn outcome_letter(&self, s: &str) -> Result<Outcome, ParseError> {
+        Outcome::ALL
+            .into_iter()
+            .find(|&outcome| self.outcome(outcome).letter == s)
+            .ok_or_else(|| ParseError::new(1, s, "an outcome letter"))
+    }
+
+    pub fn parse_match(&self, line: &str) -> Result<Match, ParseError> {
+        let (opp, you) = super::split_round(line)?;
+        Ok(Match {
+            opponent: self.opponent_shape(opp)?,
+            you: self
+                .player_shape(you)
+                .map_err(|e| e.shifted(opp.len() + 1))?,
+        })
+    }
+
+    /// Also rejects outcomes that no shape can reach against the opponent.
+    pub fn parse_strategy(&self, line: &str) -> Result<Strategy, ParseError> {
+        let (opp, out) = super::split_round(line)?;
+        let strategy = Strategy {
+            opponent: self.opponent_shape(opp)?,
+            outcome: self
+                .outcome_letter(out)
+                .map_err(|e| e.shifted(opp.len() + 1))?,
+        };
+        match self.resolve(&strategy) {
+            Some(_) => Ok(strategy),
+            None => Err(ParseError::new(
+                opp.len() + 2,
+                out,
+                "an outcome this ruleset allows",
+            )),
+        }
+    }
+
+    fn lines<T>(
+        &self,
+        input: &str,
+        parser: &mut Parser,
+        parse: impl Fn(&Self, &str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut parsed = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            match parse(self, line) {
+                Ok(value) => parsed.push(value),
+                Err(e) => parser.skip(e.at_line(index + 1))?,
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Reads the second column of every line as your shape.
+    pub fn matches(&self, input: &str, parser: &mut Parser) -> Result<Vec<Match>, ParseError> {
+        self.lines(input, parser, Self::parse_match)
+    }
+
+    /// Reads the second column of every line as the outcome.
+    pub fn strategies(
+        &self,
+        input: &str,
+        parser: &mut Parser,
+    ) -> Result<Vec<Strategy>, ParseError> {
+        self.lines(input, parser, Self::parse_strategy)
+    }
+
+    pub fn resolve(&self, strategy: &Strategy) -> Option<Match> {
+        Some(Match {
+            opponent: strategy.opponent,
+            you: self.respond(strategy.opponent, strategy.outcome)?,
+        })
+    }
+
+    pub fn match_score(&self, game: &Match) -> u32 {
+        self.score(game.you, game.opponent)
+    }
+
+    pub fn score_matches(&self, matches: &[Match]) -> u32 {
+        matches.iter().map(|game| self.match_score(game)).sum()
+    }
+
+    /// Strategies the ruleset cannot resolve score nothing.
+    pub fn score_strategies(&self, strategies: &[Strategy]) -> u32 {
+        strategies
+            .iter()
+            .filter_map(|strategy| self.resolve(strategy))
+            .map(|game| self.match_score(&game))
+            .sum()
+    }
+
+    /// Writes a strategy back as a guide line, e.g. `A Y`.
+    pub fn encode(&self, strategy: &Strategy) -> String {
+        format!(
+            "{} {}",
+            self.shape(strategy.opponent).opponent,
+            self.outcome(strategy.outcome).letter
+        )
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset::from_str(DEFAULT_RULES).expect("the default rules are valid")
+    }
+}
+
+fn parse_score(s: &str, column: usize) -> Result<u32, ParseError> {
+    s.parse().map_err(|_| ParseError::new(column, s, "a score"))
+}
+
+fn column(line: &str, field: &str) -> usize {
+    field.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+impl FromStr for Ruleset {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut shapes: Vec<ShapeRule> = Vec::new();
+        let mut beats: Vec<(usize, &str, Vec<&str>)> = Vec::new();
+        let mut outcomes: [Option<OutcomeRule>; 3] = [None, None, None];
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let error = |field: &str, expected: &str| {
+                ParseError::new(column(line, field), field, expected).at_line(line_number)
+            };
+            match fields[..] {
+                [] => {}
+                [comment, ..] if comment.starts_with('#') => {}
+                ["shape", name, score, opponent, player] => {
+                    if shapes.iter().any(|shape| shape.name == name) {
+                        return Err(error(name, "a shape name that is not already defined"));
+                    }
+                    if shapes
+                        .iter()
+                        .any(|shape| shape.opponent == opponent || shape.player == player)
+                    {
+                        return Err(error(opponent, "letters not used by another shape"));
+                    }
+                    shapes.push(ShapeRule {
+                        name: name.to_string(),
+                        score: parse_score(score, column(line, score))
+                            .map_err(|e| e.at_line(line_number))?,
+                        opponent: opponent.to_string(),
+                        player: player.to_string(),
+                    });
+                }
+                ["beats", winner, ref losers @ ..] if !losers.is_empty() => {
+                    beats.push((line_number, winner, losers.to_vec()));
+                }
+                ["outcome", kind, score, letter] => {
+                    let outcome = match kind {
+                        "loss" => Outcome::Loss,
+                        "draw" => Outcome::Draw,
+                        "win" => Outcome::Win,
+                        _ => return Err(error(kind, "loss, draw or win")),
+                    };
+                    outcomes[outcome_index(outcome)] = Some(OutcomeRule {
+                        score: parse_score(score, column(line, score))
+                            .map_err(|e| e.at_line(line_number))?,
+                        letter: letter.to_string(),
+                    });
+                }
+                [keyword, ..] => {
+                    return Err(error(
+                        keyword,
+                        "'shape name score letter letter', 'beats shape shapes...' or 'outcome kind score letter'",
+                    ))
+                }
+            }
+        }
+        let last_line = s.lines().count().max(1);
+        if shapes.is_empty() {
+            return Err(ParseError::new(1, "", "at least one shape").at_line(last_line));
+        }
+        let mut table = vec![vec![false; shapes.len()]; shapes.len()];
+        let lookup = |name: &str, line: usize, line_text: &str| {
+            shapes
+                .iter()
+                .position(|shape| shape.name == name)
+                .ok_or_else(|| {
+                    ParseError::new(column(line_text, name), name, "a defined shape").at_line(line)
+                })
+        };
+        for (line_number, winner, losers) in &beats {
+            let line = s.lines().nth(line_number - 1).unwrap();
+            let w = lookup(winner, *line_number, line)?;
+            for loser in losers {
+                let l = lookup(loser, *line_number, line)?;
+                if w == l || table[l][w] {
+                    return Err(ParseError::new(
+                        column(line, loser),
+                        *loser,
+                        "a shape that does not already beat or equal the winner",
+                    )
+                    .at_line(*line_number));
+                }
+                table[w][l] = true;
+            }
+        }
+        let [loss, draw, win] = outcomes;
+        let missing = |kind: &str| ParseError::new(1, "", format!("an outcome {} rule", kind));
+        let outcomes = [
+            loss.ok_or_else(|| missing("loss").at_line(last_line))?,
+            draw.ok_or_else(|| missing("draw").at_line(last_line))?,
+            win.ok_or_else(|| missing("win").at_line(last_line))?,
+        ];
+        Ok(Ruleset {
+            shapes,
+            beats: table,
+            outcomes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn test_default_rules() {
+        let rules = Ruleset::default();
+        let (rock, paper, scissors) = (Shape(0), Shape(1), Shape(2));
+        assert_eq!(rules.play(rock, scissors), Outcome::Win);
+        assert_eq!(rules.play(rock, paper), Outcome::Loss);
+        assert_eq!(rules.play(paper, paper), Outcome::Draw);
+        assert_eq!(rules.respond(rock, Outcome::Win), Some(paper));
+        assert_eq!(rules.score(scissors, paper), 3 + 6);
+
+        let matches = rules.matches(EXAMPLE, &mut Parser::strict(2)).unwrap();
+        assert_eq!(rules.score_matches(&matches), 15);
+        let strategies = rules.strategies(EXAMPLE, &mut Parser::strict(2)).unwrap();
+        assert_eq!(rules.score_strategies(&strategies), 12);
+        assert_eq!(rules.encode(&strategies[0]), "A Y");
+    }
+
+    #[test]
+    fn test_bad_round() {
+        let error = Ruleset::default().parse_match("A Q").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "Q"));
+        let error = Ruleset::default().parse_strategy("D X").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "D"));
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let rules = Ruleset::from_str(include_str!("../data/rules/rpsls")).unwrap();
+        assert_eq!(rules.shapes().count(), 5);
+        let (spock, lizard) = (Shape(4), Shape(3));
+        assert_eq!(rules.play(lizard, spock), Outcome::Win);
+        assert_eq!(rules.play(spock, lizard), Outcome::Loss);
+        // Rock is beaten by Paper (2) and Spock (5); the higher score wins.
+        assert_eq!(rules.respond(Shape(0), Outcome::Win), Some(spock));
+        let matches = rules.matches("A Z\nE V", &mut Parser::strict(2)).unwrap();
+        assert_eq!(rules.score_matches(&matches), (5 + 6) + 1);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let error = Ruleset::from_str("shape Rock 1 A X\nbeats Rock Paper").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 12, "Paper")
+        );
+        let error = Ruleset::from_str("shape Rock one A X").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 12));
+        let error = Ruleset::from_str("shape Rock 1 A X").unwrap_err();
+        assert_eq!(error.expected, "an outcome loss rule");
+    }
+}
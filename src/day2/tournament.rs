@@ -0,0 +1,226 @@
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
+
+use super::rules::{Ruleset, Shape};
+use super::{Match, Outcome};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    number: usize,
+    opponent: Shape,
+    you: Shape,
+    outcome: Outcome,
+    shape_score: u32,
+    outcome_score: u32,
+    running_total: u32,
+}
+
+impl Round {
+    /// The round's position in the guide, counting from 1.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn opponent(&self) -> Shape {
+        self.opponent
+    }
+
+    pub fn you(&self) -> Shape {
+        self.you
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn shape_score(&self) -> u32 {
+        self.shape_score
+    }
+
+    pub fn outcome_score(&self) -> u32 {
+        self.outcome_score
+    }
+
+    /// The score of this round and every round before it.
+    pub fn running_total(&self) -> u32 {
+        self.running_total
+    }
+
+    pub fn score(&self) -> u32 {
+        self.shape_score + self.outcome_score
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tournament {
+    rounds: Vec<Round>,
+    wins: usize,
+    draws: usize,
+    losses: usize,
+    longest_win_streak: usize,
+}
+
+pub const CSV_HEADER: &str =
+    "round,opponent,you,outcome,shape_score,outcome_score,score,running_total";
+
+impl Tournament {
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn wins(&self) -> usize {
+        self.wins
+    }
+
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    pub fn losses(&self) -> usize {
+        self.losses
+    }
+
+    pub fn longest_win_streak(&self) -> usize {
+        self.longest_win_streak
+    }
+
+    pub fn total(&self) -> u32 {
+        self.rounds.last().map_or(0, |round| round.running_total)
+    }
+
+    pub fn write_csv<W: Write>(&self, rules: &Ruleset, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for round in &self.rounds {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                round.number,
+                rules.shape(round.opponent).name(),
+                rules.shape(round.you).name(),
+                round.outcome,
+                round.shape_score,
+                round.outcome_score,
+                round.score(),
+                round.running_total
+            )?;
+        }
+        writer.flush()
+    }
+}
+
+impl Display for Tournament {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "rounds: {}", self.rounds.len())?;
+        writeln!(
+            f,
+            "wins: {}, draws: {}, losses: {}",
+            self.wins, self.draws, self.losses
+        )?;
+        writeln!(f, "longest winning streak: {}", self.longest_win_streak)?;
+        write!(f, "total score: {}", self.total())
+    }
+}
+
+pub fn simulate<'a, I: IntoIterator<Item = &'a Match>>(rules: &Ruleset, matches: I) -> Tournament {
+    let mut tournament = Tournament::default();
+    let mut streak = 0;
+    let mut running_total = 0;
+    for (index, game) in matches.into_iter().enumerate() {
+        let outcome = rules.play(game.you, game.opponent);
+        match outcome {
+            Outcome::Win => {
+                tournament.wins += 1;
+                streak += 1;
+                tournament.longest_win_streak = tournament.longest_win_streak.max(streak);
+            }
+            Outcome::Draw => {
+                tournament.draws += 1;
+                streak = 0;
+            }
+            Outcome::Loss => {
+                tournament.losses += 1;
+                streak = 0;
+            }
+        }
+        running_total += rules.match_score(game);
+        tournament.rounds.push(Round {
+            number: index + 1,
+            opponent: game.opponent,
+            you: game.you,
+            outcome,
+            shape_score: rules.shape_score(game.you),
+            outcome_score: rules.outcome_score(outcome),
+            running_total,
+        });
+    }
+    tournament
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2::Guide;
+    use crate::parse::Parser;
+    use crate::solution::Solution;
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z
+A Y
+C X";
+
+    #[test]
+    fn test_simulate() {
+        let guide = Guide::parse(EXAMPLE, &mut Parser::strict(2)).unwrap();
+        let tournament = simulate(guide.rules(), guide.matches());
+        assert_eq!(
+            (tournament.wins, tournament.draws, tournament.losses),
+            (3, 1, 1)
+        );
+        assert_eq!(tournament.longest_win_streak, 2);
+        assert_eq!(tournament.total(), guide.part1());
+        let totals: Vec<u32> = tournament
+            .rounds
+            .iter()
+            .map(|round| round.running_total)
+            .collect();
+        assert_eq!(totals, [8, 9, 15, 23, 30]);
+
+        let strategies: Vec<Match> = guide
+            .strategies()
+            .iter()
+            .filter_map(|strategy| guide.rules().resolve(strategy))
+            .collect();
+        assert_eq!(simulate(guide.rules(), &strategies).total(), guide.part2());
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let rules: Ruleset = include_str!("../data/rules/rpsls").parse().unwrap();
+        let matches = rules
+            .matches("E V\nD X\nA X", &mut Parser::strict(2))
+            .unwrap();
+        let tournament = simulate(&rules, &matches);
+        assert_eq!(
+            (tournament.wins, tournament.draws, tournament.losses),
+            (1, 0, 2)
+        );
+        assert_eq!(tournament.total(), 1 + (3 + 6) + 3);
+    }
+
+    #[test]
+    fn test_csv() {
+        let guide = Guide::parse("A Y\nB X", &mut Parser::strict(2)).unwrap();
+        let mut csv = Vec::new();
+        simulate(guide.rules(), guide.matches())
+            .write_csv(guide.rules(), &mut csv)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            format!(
+                "{}\n1,Rock,Paper,win,2,6,8,8\n2,Paper,Rock,loss,1,0,1,9\n",
+                CSV_HEADER
+            )
+        );
+    }
+}
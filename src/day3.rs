@@ -1,85 +1,303 @@
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
 use itertools::Itertools;
-use std::collections::HashSet;
 use std::str::FromStr;
 
-struct Rucksack {
-    first: Vec<u8>,
-    second: Vec<u8>,
+pub mod generate;
+pub mod items;
+
+use items::ItemSet;
+
+pub struct Rucksack {
+    compartments: Vec<ItemSet>,
 }
 
 impl Rucksack {
-    fn find_duplicate(&self) -> Option<u8> {
-        let first: HashSet<u8> = HashSet::from_iter(self.first.iter().copied());
-        let second: HashSet<u8> = HashSet::from_iter(self.second.iter().copied());
-        first.intersection(&second).next().copied()
+    /// Parses a line whose items are split evenly over `compartments`.
+    pub fn parse(s: &str, compartments: usize) -> Result<Self, ParseError> {
+        if compartments == 0 {
+            return Err(ParseError::new(1, "0", "at least one compartment"));
+        }
+        let v: Vec<u8> = s
+            .chars()
+            .enumerate()
+            .map(|(index, c)| {
+                priority(c).ok_or_else(|| ParseError::new(index + 1, c, "an item a-z or A-Z"))
+            })
+            .try_collect()?;
+        if !v.len().is_multiple_of(compartments) {
+            return Err(ParseError::new(
+                1,
+                s,
+                format!("a number of items divisible by {}", compartments),
+            ));
+        }
+        Ok(Self {
+            compartments: v
+                .chunks((v.len() / compartments).max(1))
+                .map(|items| items.iter().copied().collect())
+                .pad_using(compartments, |_| ItemSet::EMPTY)
+                .collect(),
+        })
     }
 
-    fn all_items(&self) -> HashSet<u8> {
-        return HashSet::from_iter(
-            self.first
-                .iter()
-                .copied()
-                .chain(self.second.iter().copied()),
-        );
+    pub fn compartments(&self) -> &[ItemSet] {
+        &self.compartments
+    }
+
+    /// Items found in every compartment.
+    pub fn common_items(&self) -> ItemSet {
+        self.compartments
+            .iter()
+            .copied()
+            .reduce(|acc, items| acc & items)
+            .unwrap_or_default()
+    }
+
+    pub fn find_duplicate(&self) -> Option<u8> {
+        self.common_items().first()
+    }
+
+    pub fn all_items(&self) -> ItemSet {
+        self.compartments
+            .iter()
+            .fold(ItemSet::EMPTY, |acc, &items| acc | items)
     }
 }
 
 impl FromStr for Rucksack {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let v = s.chars().map(priority).collect_vec();
-        let (first, second) = v.split_at(v.len() / 2);
-        Ok(Self {
-            first: first.to_vec(),
-            second: second.to_vec(),
-        })
+        Rucksack::parse(s, 2)
     }
 }
 
-fn priority(c: char) -> u8 {
-    if c.is_lowercase() {
-        (c as u32 as u8) - 96
-    } else {
-        (c as u32 as u8) - 38
+pub fn priority(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 27),
+        _ => None,
     }
 }
 
-fn get_rucksacks(input: &str) -> impl Iterator<Item = Rucksack> + '_ {
-    input.lines().flat_map(|line| Rucksack::from_str(line).ok())
+pub fn item(priority: u8) -> char {
+    match priority {
+        1..=26 => (b'a' + priority - 1) as char,
+        _ => (b'A' + priority - 27) as char,
+    }
 }
 
-fn duplicates_priority(rucksacks: &[Rucksack]) -> u32 {
+pub fn duplicates_priority(rucksacks: &[Rucksack]) -> u32 {
     rucksacks
         .iter()
-        .flat_map(|r| r.find_duplicate().map(|d| d as u32))
+        .filter_map(Rucksack::find_duplicate)
+        .map(u32::from)
         .sum()
 }
 
-fn find_badge<'a, G: Iterator<Item = &'a Rucksack>>(group: G) -> u32 {
-    *group
+pub fn common_items<'a, G: Iterator<Item = &'a Rucksack>>(group: G) -> ItemSet {
+    group
         .map(|rucksack| rucksack.all_items())
-        .reduce(|acc, item| HashSet::from_iter(acc.intersection(&item).copied()))
-        .unwrap()
+        .reduce(|acc, items| acc & items)
+        .unwrap_or_default()
+}
+
+pub fn find_badge<'a, G: Iterator<Item = &'a Rucksack>>(group: G) -> Option<u8> {
+    common_items(group).first()
+}
+
+pub fn groups_priority(rucksacks: &[Rucksack], size: usize) -> u32 {
+    rucksacks
         .iter()
-        .next()
-        .unwrap() as u32
+        .chunks(size.max(1))
+        .into_iter()
+        .filter_map(find_badge)
+        .map(u32::from)
+        .sum()
+}
+
+/// Consecutive rucksacks that share a badge; `line` is the line of the first.
+pub struct Group<'a> {
+    line: usize,
+    rucksacks: Vec<&'a Rucksack>,
+}
+
+impl<'a> Group<'a> {
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn rucksacks(&self) -> &[&'a Rucksack] {
+        &self.rucksacks
+    }
+
+    pub fn common_items(&self) -> ItemSet {
+        common_items(self.rucksacks.iter().copied())
+    }
+
+    pub fn badge(&self) -> Option<u8> {
+        self.common_items().first()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub compartments: usize,
+    pub group_size: usize,
+}
+
+impl Layout {
+    fn check(&self) -> Result<(), ParseError> {
+        if self.compartments == 0 {
+            return Err(ParseError::new(1, "0", "at least one compartment"));
+        }
+        if self.group_size == 0 {
+            return Err(ParseError::new(1, "0", "a group size of at least 1"));
+        }
+        Ok(())
+    }
 }
 
-fn groups_priority(rucksacks: &[Rucksack]) -> u32 {
-    rucksacks.iter().chunks(3).into_iter().map(find_badge).sum()
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            compartments: 2,
+            group_size: 3,
+        }
+    }
+}
+
+pub struct Rucksacks {
+    rucksacks: Vec<Rucksack>,
+    lines: Vec<usize>,
+    line_count: usize,
+    group_size: usize,
+}
+
+impl Rucksacks {
+    pub fn with_layout(
+        input: &str,
+        layout: Layout,
+        parser: &mut Parser,
+    ) -> Result<Self, ParseError> {
+        layout.check().map_err(|e| parser.fail(e))?;
+        let mut rucksacks = Rucksacks {
+            rucksacks: Vec::new(),
+            lines: Vec::new(),
+            line_count: input.lines().count(),
+            group_size: layout.group_size,
+        };
+        for (index, line) in input.lines().enumerate() {
+            match Rucksack::parse(line, layout.compartments) {
+                Ok(rucksack) => {
+                    rucksacks.rucksacks.push(rucksack);
+                    rucksacks.lines.push(index + 1);
+                }
+                Err(e) => parser.skip(e.at_line(index + 1))?,
+            }
+        }
+        for group in rucksacks.groups(layout.group_size, parser)? {
+            if group.common_items().is_empty() {
+                parser.skip(
+                    ParseError::new(
+                        1,
+                        format!(
+                            "no common item in lines {}-{}",
+                            group.line,
+                            group.line + layout.group_size - 1
+                        ),
+                        "an item carried by the whole group",
+                    )
+                    .at_line(group.line),
+                )?;
+            }
+        }
+        Ok(rucksacks)
+    }
+
+    pub fn rucksacks(&self) -> &[Rucksack] {
+        &self.rucksacks
+    }
+
+    /// The items each complete group has in common, in input order.
+    pub fn badges(&self) -> Vec<ItemSet> {
+        self.complete_groups(self.group_size)
+            .iter()
+            .map(Group::common_items)
+            .filter(|items| !items.is_empty())
+            .collect()
+    }
+
+    /// Groups of `size` lines that all parsed, by the line they start on.
+    fn complete_groups(&self, size: usize) -> Vec<Group<'_>> {
+        let mut groups: Vec<Group<'_>> = Vec::new();
+        for (line, rucksack) in self.lines.iter().zip(&self.rucksacks) {
+            let start = line - (line - 1) % size;
+            match groups.last_mut() {
+                Some(group) if group.line == start => group.rucksacks.push(rucksack),
+                _ => groups.push(Group {
+                    line: start,
+                    rucksacks: vec![rucksack],
+                }),
+            }
+        }
+        groups.retain(|group| group.rucksacks.len() == size);
+        groups
+    }
+
+    /// Splits the input into groups of `size` lines. Groups with a line that
+    /// failed to parse are left out, and a short final group is reported.
+    pub fn groups(&self, size: usize, parser: &mut Parser) -> Result<Vec<Group<'_>>, ParseError> {
+        if size == 0 {
+            return Err(parser.fail(ParseError::new(1, "0", "a group size of at least 1")));
+        }
+        let short = self.line_count % size;
+        if short != 0 {
+            parser.skip(
+                ParseError::new(
+                    1,
+                    format!("{} rucksack{}", short, if short == 1 { "" } else { "s" }),
+                    format!("a final group of {} rucksacks", size),
+                )
+                .at_line(self.line_count - short + 1),
+            )?;
+        }
+        Ok(self.complete_groups(size))
+    }
 }
 
-pub fn main() {
-    let input = include_str!("data/day3");
-    let rucksacks = get_rucksacks(input).collect_vec();
-    println!("{}", duplicates_priority(&rucksacks));
-    println!("{}", groups_priority(&rucksacks));
+impl Solution for Rucksacks {
+    const DAY: u8 = 3;
+
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        Rucksacks::with_layout(input, Layout::default(), parser)
+    }
+
+    fn part1(&self) -> u32 {
+        duplicates_priority(&self.rucksacks)
+    }
+
+    fn part2(&self) -> u32 {
+        self.complete_groups(self.group_size)
+            .iter()
+            .filter_map(Group::badge)
+            .map(u32::from)
+            .sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::Mode;
+
+    fn get_rucksacks(input: &str) -> Vec<Rucksack> {
+        Parser::strict(3).lines(input).unwrap()
+    }
 
     const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
@@ -90,13 +308,130 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_duplicates_priority() {
+        assert_eq!(157, duplicates_priority(&get_rucksacks(EXAMPLE)));
+    }
+    #[test]
+    fn test_groups_priority() {
+        assert_eq!(70, groups_priority(&get_rucksacks(EXAMPLE), 3));
+    }
+
+    #[test]
+    fn test_layout() {
+        let layout = Layout {
+            compartments: 3,
+            group_size: 2,
+        };
+        let rucksacks = Rucksacks::with_layout(
+            "abcaxa\nbbaayc\naBcaBcaBc",
+            layout,
+            &mut Parser::new(3, Mode::Lenient),
+        )
+        .unwrap();
+        let common: Vec<ItemSet> = rucksacks
+            .rucksacks()
+            .iter()
+            .map(Rucksack::common_items)
+            .collect();
+        assert_eq!(common[0].iter().map(item).collect::<String>(), "a");
+        assert_eq!(common[1].iter().map(item).collect::<String>(), "");
+        assert_eq!(common[2].iter().map(item).collect::<String>(), "acB");
+        assert_eq!(rucksacks.badges().len(), 1);
         assert_eq!(
-            157,
-            duplicates_priority(&get_rucksacks(EXAMPLE).collect_vec())
+            rucksacks.badges()[0].iter().map(item).collect::<String>(),
+            "abc"
         );
     }
+
     #[test]
-    fn test_groups_priority() {
-        assert_eq!(70, groups_priority(&get_rucksacks(EXAMPLE).collect_vec()));
+    fn test_priority() {
+        assert_eq!(priority('a'), Some(1));
+        assert_eq!(priority('Z'), Some(52));
+        assert_eq!(priority('1'), None);
+        assert_eq!(priority('é'), None);
+    }
+
+    #[test]
+    fn test_bad_lines() {
+        let lines: Vec<&str> = EXAMPLE.lines().collect();
+        let input = format!(
+            "{}\nab1d\nabc\n{}\n{}\nabab\ncdcd\nefef\nAa",
+            EXAMPLE,
+            lines[3],
+            lines[..3].join("\n")
+        );
+        let mut parser = Parser::new(3, Mode::Lenient);
+        let rucksacks = Rucksacks::parse(&input, &mut parser).unwrap();
+        assert_eq!(rucksacks.rucksacks().len(), 14);
+        assert_eq!(rucksacks.part1(), 157 + 22 + 96 + 9);
+        assert_eq!(rucksacks.part2(), 70 + 18);
+        let warnings: Vec<_> = parser
+            .warnings()
+            .iter()
+            .map(|w| (w.line, w.column, w.text.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (7, 3, "1"),
+                (8, 1, "abc"),
+                (16, 1, "1 rucksack"),
+                (13, 1, "no common item in lines 13-15"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_common_item() {
+        let mut parser = Parser::strict(3);
+        let rucksacks = Rucksacks::parse("abab\nbcab\nbaaa", &mut parser).unwrap();
+        let common: Vec<String> = rucksacks
+            .rucksacks()
+            .iter()
+            .map(|rucksack| rucksack.common_items().iter().map(item).collect())
+            .collect();
+        assert_eq!(common, ["ab", "b", "a"]);
+        let groups = rucksacks.groups(3, &mut parser).unwrap();
+        assert_eq!(
+            groups[0]
+                .common_items()
+                .iter()
+                .map(item)
+                .collect::<String>(),
+            "ab"
+        );
+        assert_eq!(groups[0].badge(), Some(1));
+        // The answers count one item per rucksack and per group, as in the puzzle.
+        assert_eq!(rucksacks.part1(), 1 + 2 + 1);
+        assert_eq!(rucksacks.part2(), 1);
+    }
+
+    #[test]
+    fn test_zero_layout() {
+        assert!(Rucksack::parse("abab", 0).is_err());
+        let mut parser = Parser::new(3, Mode::Lenient);
+        for (compartments, group_size) in [(0, 3), (2, 0)] {
+            let layout = Layout {
+                compartments,
+                group_size,
+            };
+            let error = Rucksacks::with_layout(EXAMPLE, layout, &mut parser)
+                .err()
+                .unwrap();
+            assert_eq!(error.day, 3);
+        }
+        let rucksacks = Rucksacks::parse(EXAMPLE, &mut parser).unwrap();
+        assert!(rucksacks.groups(0, &mut parser).is_err());
+        assert!(parser.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_strict() {
+        let error = Rucksacks::parse("vJrw\nabc", &mut Parser::strict(3))
+            .err()
+            .unwrap();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (2, 1, "a number of items divisible by 2")
+        );
     }
 }
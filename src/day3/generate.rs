@@ -0,0 +1,80 @@
+use super::item;
+
+/// Xorshift64, enough to make reproducible inputs without a dependency.
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Generates `groups` groups of three rucksacks. Every rucksack has exactly one
+/// item in both compartments and every group has exactly one item in common,
+/// its badge.
+pub fn rucksacks(groups: usize, seed: u64) -> String {
+    let mut random = Random::new(seed);
+    let mut output = String::new();
+    for _ in 0..groups {
+        let badge = random.below(52) as u8 + 1;
+        // Leave every other item out of one rucksack so only the badge is shared.
+        let left_out: Vec<usize> = (0..=52).map(|_| random.below(3)).collect();
+        for rucksack in 0..3 {
+            let mut pool: Vec<u8> = (1..=52)
+                .filter(|&p| p != badge && left_out[p as usize] != rucksack)
+                .collect();
+            for i in (1..pool.len()).rev() {
+                pool.swap(i, random.below(i + 1));
+            }
+            let duplicate = pool.pop().unwrap();
+            let (left, right) = pool.split_at(pool.len() / 2);
+            let size = 8 + random.below(16);
+            let mut first: Vec<u8> = (1..size).map(|_| left[random.below(left.len())]).collect();
+            let mut second: Vec<u8> = (2..size)
+                .map(|_| right[random.below(right.len())])
+                .collect();
+            first.insert(random.below(first.len() + 1), duplicate);
+            second.insert(random.below(second.len() + 1), duplicate);
+            second.insert(random.below(second.len() + 1), badge);
+            output.extend(first.into_iter().chain(second).map(item));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day3::Rucksacks;
+    use crate::parse::Parser;
+    use crate::solution::Solution;
+
+    #[test]
+    fn test_rucksacks() {
+        let input = rucksacks(100, 7);
+        assert_eq!(input, rucksacks(100, 7));
+        assert_eq!(input.lines().count(), 300);
+        assert!(input.lines().all(|line| line.len() % 2 == 0));
+        let rucksacks = Rucksacks::parse(&input, &mut Parser::strict(3)).unwrap();
+        for rucksack in rucksacks.rucksacks() {
+            assert_eq!(rucksack.common_items().len(), 1);
+        }
+        let groups = rucksacks.groups(3, &mut Parser::strict(3)).unwrap();
+        assert_eq!(groups.len(), 100);
+        for group in groups {
+            assert_eq!(group.common_items().len(), 1);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub};
+
+/// A set of item priorities (1..=52) packed into the bits of a `u64`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+    pub const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    pub fn new() -> Self {
+        ItemSet::EMPTY
+    }
+
+    pub fn from_bits(bits: u64) -> Self {
+        ItemSet(bits & ItemSet::ALL.0)
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Panics unless `priority` is in 1..=52.
+    pub fn insert(&mut self, priority: u8) {
+        assert!(
+            (1..=52).contains(&priority),
+            "item priority {} is out of range",
+            priority
+        );
+        self.0 |= 1 << priority;
+    }
+
+    /// Priorities outside 1..=52 are never in the set, so removing them does nothing.
+    pub fn remove(&mut self, priority: u8) {
+        if priority < 64 {
+            self.0 &= !(1 << priority);
+        }
+    }
+
+    pub fn contains(&self, priority: u8) -> bool {
+        priority < 64 && self.0 & (1 << priority) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & !other.0)
+    }
+
+    /// The lowest priority in the set.
+    pub fn first(&self) -> Option<u8> {
+        (!self.is_empty()).then(|| self.0.trailing_zeros() as u8)
+    }
+
+    pub fn iter(&self) -> Items {
+        Items(self.0)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        self.intersection(other)
+    }
+}
+
+impl BitAndAssign for ItemSet {
+    fn bitand_assign(&mut self, other: ItemSet) {
+        *self = *self & other;
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for ItemSet {
+    fn bitor_assign(&mut self, other: ItemSet) {
+        *self = *self | other;
+    }
+}
+
+impl Sub for ItemSet {
+    type Output = ItemSet;
+
+    fn sub(self, other: ItemSet) -> ItemSet {
+        self.difference(other)
+    }
+}
+
+impl FromIterator<u8> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(priorities: I) -> Self {
+        let mut set = ItemSet::new();
+        set.extend(priorities);
+        set
+    }
+}
+
+impl Extend<u8> for ItemSet {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, priorities: I) {
+        for priority in priorities {
+            self.insert(priority);
+        }
+    }
+}
+
+/// Iterates the priorities of an `ItemSet` in ascending order.
+pub struct Items(u64);
+
+impl Iterator for Items {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+        let priority = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Some(priority)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Items {}
+
+impl IntoIterator for ItemSet {
+    type Item = u8;
+    type IntoIter = Items;
+
+    fn into_iter(self) -> Items {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_operations() {
+        let a: ItemSet = [1, 5, 52].into_iter().collect();
+        let b: ItemSet = [5, 16, 52].into_iter().collect();
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [5, 52]);
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), [1, 5, 16, 52]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), [1]);
+        assert_eq!((a | b).len(), 4);
+        assert_eq!(a.first(), Some(1));
+        assert!(a.contains(52) && !a.contains(16) && !a.contains(0));
+        assert_eq!(ItemSet::EMPTY.first(), None);
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert_eq!(ItemSet::from_bits(u64::MAX), ItemSet::ALL);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = ItemSet::new();
+        set.insert(27);
+        set.insert(27);
+        assert_eq!(set.len(), 1);
+        set.remove(27);
+        set.remove(64);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_insert_out_of_range() {
+        ItemSet::new().insert(64);
+    }
+}
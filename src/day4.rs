@@ -1,67 +1,172 @@
-use itertools::Itertools;
-use std::collections::HashSet;
+use crate::interval::{Interval, IntervalSet};
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
 use std::str::FromStr;
 
-struct Pair {
-    first: HashSet<u8>,
-    second: HashSet<u8>,
+pub mod render;
+pub mod sweep;
+
+pub struct Pair {
+    first: Interval,
+    second: Interval,
 }
 
 impl FromStr for Pair {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first, second) = s.split_once(',').ok_or(())?;
+        let (first, second) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::new(1, s, "two ranges separated by a comma"))?;
         Ok(Pair {
             first: parse_range(first)?,
-            second: parse_range(second)?,
+            second: parse_range(second).map_err(|e| e.shifted(first.len() + 1))?,
         })
     }
 }
 
 impl Pair {
-    fn contained(&self) -> bool {
-        self.first.is_subset(&self.second) || self.second.is_subset(&self.first)
+    pub fn first(&self) -> Interval {
+        self.first
+    }
+
+    pub fn second(&self) -> Interval {
+        self.second
     }
 
-    fn overlapping(&self) -> bool {
-        self.first.intersection(&self.second).next().is_some()
+    pub fn contained(&self) -> bool {
+        self.first.covers(&self.second) || self.second.covers(&self.first)
+    }
+
+    pub fn overlapping(&self) -> bool {
+        self.first.overlaps(&self.second)
     }
 }
 
-fn parse_range(s: &str) -> Result<HashSet<u8>, ()> {
-    let (low, high) = s.split_once('-').ok_or(())?;
-    Ok(HashSet::from_iter(
-        (low.parse().map_err(|_| ())?)..=(high.parse().map_err(|_| ())?),
-    ))
+fn parse_section(s: &str, column: usize) -> Result<u64, ParseError> {
+    s.parse()
+        .map_err(|_| ParseError::new(column, s, "a section number"))
 }
 
-fn count_containing_sections(pairs: &[Pair]) -> usize {
+fn parse_range(s: &str) -> Result<Interval, ParseError> {
+    let (low, high) = s
+        .split_once('-')
+        .ok_or_else(|| ParseError::new(1, s, "a section range like 2-4"))?;
+    let start = parse_section(low, 1)?;
+    let end = parse_section(high, low.len() + 2)?;
+    Interval::new(start, end).ok_or_else(|| {
+        ParseError::new(
+            low.len() + 2,
+            high,
+            format!("a section number of at least {}", start),
+        )
+    })
+}
+
+pub fn count_containing_sections(pairs: &[Pair]) -> usize {
     pairs.iter().filter(|pair| pair.contained()).count()
 }
 
-fn count_overlapping_sections(pairs: &[Pair]) -> usize {
+pub fn count_overlapping_sections(pairs: &[Pair]) -> usize {
     pairs.iter().filter(|pair| pair.overlapping()).count()
 }
 
-fn parse_pairs(input: &str) -> Vec<Pair> {
-    input
-        .lines()
-        .flat_map(|line| Pair::from_str(line).ok())
-        .collect_vec()
+fn elves(pairs: &[Pair]) -> impl Iterator<Item = Interval> + '_ {
+    pairs.iter().flat_map(|pair| [pair.first, pair.second])
+}
+
+/// Every section assigned to at least one elf.
+pub fn coverage(pairs: &[Pair]) -> IntervalSet {
+    elves(pairs).collect()
+}
+
+/// The sections within `bounds` that no elf has to clean.
+pub fn uncovered(pairs: &[Pair], bounds: Interval) -> IntervalSet {
+    coverage(pairs).complement(bounds)
 }
 
-pub fn main() {
-    let input = include_str!("data/day4");
-    let pairs = parse_pairs(input);
-    println!("{}", count_containing_sections(&pairs));
-    println!("{}", count_overlapping_sections(&pairs));
-    // 621 ng
+/// The sections assigned to more than one elf, across all pairs, found by
+/// sweeping the range endpoints in order.
+pub fn multiply_covered(pairs: &[Pair]) -> IntervalSet {
+    let events = sweep::events(elves(pairs));
+    let mut shared = IntervalSet::new();
+    let mut depth = 0;
+    for (index, &(position, starts)) in events.iter().enumerate() {
+        if starts {
+            depth += 1;
+        } else {
+            depth -= 1;
+        }
+        match events.get(index + 1) {
+            Some(&(next, _)) if next > position && depth > 1 => {
+                shared.extend(Interval::new(position as u64, (next - 1) as u64))
+            }
+            _ => {}
+        }
+    }
+    shared
+}
+
+pub struct Assignments {
+    pairs: Vec<Pair>,
+    lines: Vec<usize>,
+}
+
+impl Assignments {
+    pub fn pairs(&self) -> &[Pair] {
+        &self.pairs
+    }
+
+    /// Every elf's range with the input line it was assigned on.
+    pub fn elves(&self) -> Vec<(usize, Interval)> {
+        self.lines
+            .iter()
+            .zip(&self.pairs)
+            .flat_map(|(&line, pair)| [(line, pair.first), (line, pair.second)])
+            .collect()
+    }
+}
+
+impl Solution for Assignments {
+    const DAY: u8 = 4;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        let mut assignments = Assignments {
+            pairs: Vec::new(),
+            lines: Vec::new(),
+        };
+        for (index, line) in input.lines().enumerate() {
+            match Pair::from_str(line) {
+                Ok(pair) => {
+                    assignments.pairs.push(pair);
+                    assignments.lines.push(index + 1);
+                }
+                Err(e) => parser.skip(e.at_line(index + 1))?,
+            }
+        }
+        Ok(assignments)
+    }
+
+    fn part1(&self) -> usize {
+        count_containing_sections(&self.pairs)
+    }
+
+    fn part2(&self) -> usize {
+        count_overlapping_sections(&self.pairs)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::Mode;
+
+    fn parse_pairs(input: &str) -> Vec<Pair> {
+        Parser::strict(4).lines(input).unwrap()
+    }
 
     const EXAMPLE: &str = "2-4,6-8
 2-3,4-5
@@ -79,4 +184,55 @@ mod tests {
     fn test_overlapping() {
         assert_eq!(4, count_overlapping_sections(&parse_pairs(EXAMPLE)));
     }
+
+    #[test]
+    fn test_bad_line() {
+        let mut parser = Parser::new(4, Mode::Lenient);
+        let pairs: Vec<Pair> = parser.lines("2-4,6-8\n2-3,4-x\n5-7,7-9").unwrap();
+        assert_eq!(pairs.len(), 2);
+        let warning = &parser.warnings()[0];
+        assert_eq!((warning.line, warning.column), (2, 7));
+        assert_eq!(warning.text, "x");
+    }
+
+    #[test]
+    fn test_coverage() {
+        let pairs = parse_pairs(EXAMPLE);
+        let covered = coverage(&pairs);
+        assert_eq!(covered.to_string(), "2-9");
+        assert_eq!(covered.coverage(), 8);
+        let bounds = Interval::new(1, 12).unwrap();
+        assert_eq!(uncovered(&pairs, bounds).to_string(), "1-1, 10-12");
+        assert_eq!(multiply_covered(&pairs).to_string(), "2-8");
+
+        let pairs = parse_pairs("1-2,5-6\n6-9,12-12");
+        assert_eq!(
+            coverage(&pairs).gaps(),
+            [Interval::new(3, 4).unwrap(), Interval::new(10, 11).unwrap()]
+        );
+        assert_eq!(multiply_covered(&pairs).to_string(), "6-6");
+
+        let pairs = parse_pairs(
+            "1-3,4-6\n3-3,18446744073709551615-18446744073709551615\n10-20,15-18446744073709551615",
+        );
+        assert_eq!(
+            multiply_covered(&pairs).to_string(),
+            "3-3, 15-20, 18446744073709551615-18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn test_large_sections() {
+        let pairs =
+            parse_pairs("300-70000,4000-5000\n18446744073709551614-18446744073709551615,0-1");
+        assert!(pairs[0].contained());
+        assert_eq!(pairs[0].first().len(), 69701);
+        assert!(!pairs[1].overlapping());
+    }
+
+    #[test]
+    fn test_reversed_range() {
+        let error = Parser::strict(4).lines::<Pair>("2-4,8-6").err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (7, "6"));
+    }
 }
@@ -0,0 +1,216 @@
+use crate::day4::Pair;
+use crate::interval::Interval;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// The widest strip drawn without compression.
+pub const MAX_COLUMNS: usize = 1000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Assigned,
+    Overlap,
+}
+
+/// Draws pairs as `.234.....` strips over `bounds`, one column per section
+/// unless compressed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Renderer {
+    bounds: Interval,
+    columns: Option<usize>,
+    colour: bool,
+}
+
+impl Renderer {
+    pub fn new(bounds: Interval) -> Self {
+        Renderer {
+            bounds,
+            columns: None,
+            colour: false,
+        }
+    }
+
+    /// Scales the sections to at most `columns` characters.
+    pub fn compressed(mut self, columns: usize) -> Self {
+        self.columns = Some(columns.max(1));
+        self
+    }
+
+    pub fn coloured(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    /// The number of sections in the bounds, which does not fit a `u64` for
+    /// the full range.
+    fn span(&self) -> u128 {
+        (self.bounds.end() - self.bounds.start()) as u128 + 1
+    }
+
+    fn is_compressed(&self) -> bool {
+        self.columns
+            .is_some_and(|columns| (columns as u128) < self.span())
+    }
+
+    /// The sections drawn in each column.
+    fn columns(&self) -> Result<Vec<Interval>, String> {
+        let start = self.bounds.start() as u128;
+        let span = self.span();
+        let columns = match self.columns {
+            Some(columns) if self.is_compressed() => columns as u128,
+            _ if span > MAX_COLUMNS as u128 => {
+                return Err(format!(
+                    "drawing {} sections needs as many columns; compress to at most {}",
+                    span, MAX_COLUMNS
+                ))
+            }
+            _ => span,
+        };
+        Ok((0..columns)
+            .map(|column| {
+                let low = start + column * span / columns;
+                let high = start + (column + 1) * span / columns - 1;
+                Interval::new(low as u64, high as u64).expect("columns cover a section each")
+            })
+            .collect())
+    }
+
+    fn strip(&self, columns: &[Interval], elf: Interval, partner: Interval) -> String {
+        let shared = elf.intersection(&partner);
+        let mut strip = String::new();
+        for column in columns {
+            let cell = if shared.is_some_and(|shared| shared.overlaps(column)) {
+                Cell::Overlap
+            } else if elf.overlaps(column) {
+                Cell::Assigned
+            } else {
+                Cell::Empty
+            };
+            let symbol = match (cell, self.is_compressed()) {
+                (Cell::Empty, _) => '.',
+                (Cell::Overlap, _) if !self.colour => '#',
+                (_, true) => '=',
+                (_, false) => char::from_digit((column.start() % 10) as u32, 10).unwrap(),
+            };
+            match (cell, self.colour) {
+                (Cell::Assigned, true) => strip.extend([GREEN, &symbol.to_string(), RESET]),
+                (Cell::Overlap, true) => strip.extend([RED, &symbol.to_string(), RESET]),
+                _ => strip.push(symbol),
+            }
+        }
+        strip
+    }
+
+    fn draw(&self, columns: &[Interval], pair: &Pair) -> String {
+        let (first, second) = (pair.first(), pair.second());
+        format!(
+            "{}  {}\n{}  {}\n",
+            self.strip(columns, first, second),
+            first,
+            self.strip(columns, second, first),
+            second
+        )
+    }
+
+    /// Fails if the bounds are wider than [`MAX_COLUMNS`] and not compressed.
+    pub fn render_pair(&self, pair: &Pair) -> Result<String, String> {
+        Ok(self.draw(&self.columns()?, pair))
+    }
+
+    pub fn render(&self, pairs: &[Pair]) -> Result<String, String> {
+        let columns = self.columns()?;
+        Ok(pairs
+            .iter()
+            .map(|pair| self.draw(&columns, pair))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+
+    fn pairs(input: &str) -> Vec<Pair> {
+        Parser::strict(4).lines(input).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let renderer = Renderer::new(Interval::new(1, 9).unwrap());
+        let pairs = pairs(EXAMPLE);
+        assert_eq!(
+            renderer.render_pair(&pairs[0]).unwrap(),
+            ".234.....  2-4\n.....678.  6-8\n"
+        );
+        assert_eq!(
+            renderer.render_pair(&pairs[2]).unwrap(),
+            "....56#..  5-7\n......#89  7-9\n"
+        );
+        assert_eq!(renderer.render(&pairs).unwrap().lines().count(), 6 * 3 - 1);
+    }
+
+    #[test]
+    fn test_compressed() {
+        let renderer = Renderer::new(Interval::new(1, 100).unwrap()).compressed(10);
+        assert_eq!(
+            renderer.render_pair(&pairs("1-10,5-50")[0]).unwrap(),
+            "#.........  1-10\n#====.....  5-50\n"
+        );
+        let wide = Renderer::new(Interval::new(1, 9).unwrap()).compressed(80);
+        assert_eq!(
+            wide.render_pair(&pairs("2-4,6-8")[0]).unwrap(),
+            ".234.....  2-4\n.....678.  6-8\n"
+        );
+    }
+
+    #[test]
+    fn test_colour() {
+        let renderer = Renderer::new(Interval::new(1, 3).unwrap()).coloured(true);
+        assert_eq!(
+            renderer.render_pair(&pairs("1-2,2-3")[0]).unwrap(),
+            format!("{GREEN}1{RESET}{RED}2{RESET}.  1-2\n.{RED}2{RESET}{GREEN}3{RESET}  2-3\n")
+        );
+    }
+    #[test]
+    fn test_full_range() {
+        let renderer = Renderer::new(Interval::new(0, u64::MAX).unwrap()).compressed(4);
+        let quarter = 1 << 62;
+        assert_eq!(
+            renderer.columns().unwrap(),
+            vec![
+                Interval::new(0, quarter - 1).unwrap(),
+                Interval::new(quarter, 2 * quarter - 1).unwrap(),
+                Interval::new(2 * quarter, 3 * quarter - 1).unwrap(),
+                Interval::new(3 * quarter, u64::MAX).unwrap(),
+            ]
+        );
+        let last = pairs(&format!("{0}-{0},0-{0}", u64::MAX));
+        assert!(renderer
+            .render_pair(&last[0])
+            .unwrap()
+            .starts_with("...#  "));
+    }
+
+    #[test]
+    fn test_too_wide() {
+        let bounds = Interval::new(1, MAX_COLUMNS as u64 + 1).unwrap();
+        let pairs = pairs("1-2,3-4");
+        assert!(Renderer::new(bounds).render(&pairs).is_err());
+        assert!(Renderer::new(Interval::new(0, u64::MAX).unwrap())
+            .render(&pairs)
+            .is_err());
+        assert!(Renderer::new(bounds).compressed(80).render(&pairs).is_ok());
+    }
+}
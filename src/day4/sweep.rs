@@ -0,0 +1,169 @@
+use std::collections::BTreeSet;
+
+use crate::interval::{Interval, IntervalSet};
+
+/// The most elves assigned to a single section, and where that happens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Busiest {
+    elves: usize,
+    sections: IntervalSet,
+}
+
+impl Busiest {
+    pub fn elves(&self) -> usize {
+        self.elves
+    }
+
+    pub fn sections(&self) -> &IntervalSet {
+        &self.sections
+    }
+}
+
+/// Sweeps over the start and end of every range, tracking how many elves
+/// are assigned to the sections in between.
+/// The start and end of every interval in order, with ends before starts at
+/// the same position.
+pub(super) fn events(intervals: impl Iterator<Item = Interval>) -> Vec<(u128, bool)> {
+    // Half-open in u128 so a range ending at u64::MAX still has an end event.
+    let mut events: Vec<(u128, bool)> = intervals
+        .flat_map(|interval| {
+            [
+                (interval.start() as u128, true),
+                (interval.end() as u128 + 1, false),
+            ]
+        })
+        .collect();
+    events.sort_unstable();
+    events
+}
+
+pub fn busiest_sections(elves: &[(usize, Interval)]) -> Busiest {
+    let events = events(elves.iter().map(|&(_, interval)| interval));
+    let mut busiest = Busiest {
+        elves: 0,
+        sections: IntervalSet::new(),
+    };
+    let mut depth = 0;
+    for (index, &(position, starts)) in events.iter().enumerate() {
+        if starts {
+            depth += 1;
+        } else {
+            depth -= 1;
+        }
+        let next = match events.get(index + 1) {
+            Some(&(next, _)) if next > position => next,
+            _ => continue,
+        };
+        if depth == 0 || depth < busiest.elves {
+            continue;
+        }
+        if depth > busiest.elves {
+            busiest = Busiest {
+                elves: depth,
+                sections: IntervalSet::new(),
+            };
+        }
+        busiest
+            .sections
+            .extend(Interval::new(position as u64, (next - 1) as u64));
+    }
+    busiest
+}
+
+/// Every pair of input lines with at least one overlapping range, found by
+/// sweeping the ranges in start order against those still open.
+pub fn overlapping_lines(elves: &[(usize, Interval)]) -> Vec<(usize, usize)> {
+    let mut order: Vec<&(usize, Interval)> = elves.iter().collect();
+    order.sort_unstable_by_key(|(_, interval)| interval.start());
+    let mut open: BTreeSet<(u64, usize, usize)> = BTreeSet::new();
+    let mut pairs = Vec::new();
+    for (id, &&(line, interval)) in order.iter().enumerate() {
+        while open
+            .first()
+            .is_some_and(|&(end, _, _)| end < interval.start())
+        {
+            open.pop_first();
+        }
+        pairs.extend(
+            open.iter()
+                .filter(|&&(_, _, other)| other != line)
+                .map(|&(_, _, other)| (other.min(line), other.max(line))),
+        );
+        open.insert((interval.end(), id, line));
+    }
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day4::Assignments;
+    use crate::parse::Parser;
+    use crate::solution::Solution;
+
+    const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+
+    fn elves(input: &str) -> Vec<(usize, Interval)> {
+        Assignments::parse(input, &mut Parser::strict(4))
+            .unwrap()
+            .elves()
+    }
+
+    fn quadratic(elves: &[(usize, Interval)]) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = elves
+            .iter()
+            .flat_map(|a| elves.iter().map(move |b| (a, b)))
+            .filter(|((a, x), (b, y))| a < b && x.overlaps(y))
+            .map(|((a, _), (b, _))| (*a, *b))
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    #[test]
+    fn test_busiest_sections() {
+        let busiest = busiest_sections(&elves(EXAMPLE));
+        assert_eq!(busiest.elves, 8);
+        assert_eq!(busiest.sections.to_string(), "6-6");
+    }
+
+    #[test]
+    fn test_overlapping_lines() {
+        let elves = elves(EXAMPLE);
+        let pairs = overlapping_lines(&elves);
+        assert_eq!(pairs, quadratic(&elves));
+        assert!(pairs.contains(&(1, 3)));
+        assert_eq!(
+            overlapping_lines(&self::elves("1-2,3-4\n5-6,7-8\n2-5,9-9")),
+            [(1, 3), (2, 3)]
+        );
+    }
+
+    #[test]
+    fn test_input_matches_quadratic() {
+        let input: String = include_str!("../data/day4")
+            .lines()
+            .take(200)
+            .map(|line| line.to_string() + "\n")
+            .collect();
+        let elves = elves(&input);
+        assert_eq!(overlapping_lines(&elves), quadratic(&elves));
+    }
+
+    #[test]
+    fn test_several_busiest_sections() {
+        let busiest = busiest_sections(&elves(
+            "1-2,2-3\n7-7,7-8\n18446744073709551615-18446744073709551615,0-0",
+        ));
+        assert_eq!(busiest.elves, 2);
+        assert_eq!(busiest.sections.to_string(), "2-2, 7-7");
+    }
+}
@@ -1,35 +1,68 @@
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
 use itertools::Itertools;
 use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 
-type Stacks = HashMap<usize, VecDeque<char>>;
+pub type Stacks = HashMap<usize, VecDeque<char>>;
 
-fn to_usize(s: &str) -> Result<usize, ()> {
-    s.parse().map_err(|_| ())
+fn to_usize(s: &str, column: usize) -> Result<usize, ParseError> {
+    s.parse()
+        .map_err(|_| ParseError::new(column, s, "a number"))
 }
 
-struct Move {
+pub struct Move {
     amount: usize,
     from: usize,
     to: usize,
 }
 
+impl Move {
+    pub fn new(amount: usize, from: usize, to: usize) -> Self {
+        Move { amount, from, to }
+    }
+
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
+
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn to(&self) -> usize {
+        self.to
+    }
+}
+
 impl FromStr for Move {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (amount, direction) = &s[5..].split_once(" from ").ok_or(())?;
-        let (from, to) = direction.split_once(" to ").ok_or(())?;
+        let malformed = || ParseError::new(1, s, "a move like 'move 1 from 2 to 3'");
+        let (amount, direction) = s
+            .strip_prefix("move ")
+            .and_then(|rest| rest.split_once(" from "))
+            .ok_or_else(malformed)?;
+        let (from, to) = direction.split_once(" to ").ok_or_else(malformed)?;
+        let from_column = 6 + amount.len() + 6;
         Ok(Move {
-            amount: to_usize(amount)?,
-            from: to_usize(from)?,
-            to: to_usize(to)?,
+            amount: to_usize(amount, 6)?,
+            from: to_usize(from, from_column)?,
+            to: to_usize(to, from_column + from.len() + 4)?,
         })
     }
 }
 
+/// Every stack named in the label row exists, even if it starts empty.
 fn parse_initial(s: &str) -> Stacks {
     let mut initial: Stacks = HashMap::new();
+    for label in s.lines().last().unwrap_or("").split_whitespace() {
+        if let Ok(index) = label.parse() {
+            initial.entry(index).or_default();
+        }
+    }
     for line in s.lines() {
         for (index, chunk) in line.chars().chunks(4).into_iter().enumerate() {
             match chunk.into_iter().nth(1) {
@@ -43,81 +76,333 @@ fn parse_initial(s: &str) -> Stacks {
     initial
 }
 
-fn crate_mover_9000(mut stacks: Stacks, instruction: &Move) -> Stacks {
-    let mut crane = VecDeque::with_capacity(instruction.amount);
-    for _ in 0..instruction.amount {
-        crane.push_back(
-            stacks
-                .get_mut(&instruction.from)
-                .unwrap()
-                .pop_front()
-                .unwrap(),
+/// Moves crates between stacks; `Plan::execute` applies it to every move.
+pub trait Crane {
+    fn name(&self) -> String;
+
+    fn apply(&mut self, stacks: Stacks, instruction: &Move) -> Result<Stacks, String>;
+}
+
+impl<F: FnMut(Stacks, &Move) -> Result<Stacks, String>> Crane for F {
+    fn name(&self) -> String {
+        "custom".to_string()
+    }
+
+    fn apply(&mut self, stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+        self(stacks, instruction)
+    }
+}
+
+impl Crane for Box<dyn Crane> {
+    fn name(&self) -> String {
+        self.as_ref().name()
+    }
+
+    fn apply(&mut self, stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+        self.as_mut().apply(stacks, instruction)
+    }
+}
+
+/// Takes `amount` crates off `from` and puts them on `to`, either all at
+/// once (keeping their order) or one at a time.
+fn lift(
+    mut stacks: Stacks,
+    from: usize,
+    to: usize,
+    amount: usize,
+    at_once: bool,
+) -> Result<Stacks, String> {
+    if !stacks.contains_key(&to) {
+        return Err(format!("there is no stack {}", to));
+    }
+    let source = stacks
+        .get_mut(&from)
+        .ok_or_else(|| format!("there is no stack {}", from))?;
+    if source.len() < amount {
+        return Err(format!(
+            "cannot lift {} crates from stack {}, which holds {}",
+            amount,
+            from,
+            source.len()
+        ));
+    }
+    let mut crane = VecDeque::with_capacity(amount);
+    for c in source.drain(..amount) {
+        if at_once {
+            crane.push_back(c)
+        } else {
+            crane.push_front(c)
+        }
+    }
+    let target = stacks.get_mut(&to).expect("checked above");
+    for c in crane.into_iter().rev() {
+        target.push_front(c)
+    }
+    Ok(stacks)
+}
+
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "9000".to_string()
+    }
+
+    fn apply(&mut self, stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+        lift(
+            stacks,
+            instruction.from,
+            instruction.to,
+            instruction.amount,
+            false,
         )
     }
-    for c in crane {
-        stacks.get_mut(&instruction.to).unwrap().push_front(c)
+}
+
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "9001".to_string()
     }
-    stacks
+
+    fn apply(&mut self, stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+        lift(
+            stacks,
+            instruction.from,
+            instruction.to,
+            instruction.amount,
+            true,
+        )
+    }
+}
+
+/// Lifts at most `capacity` crates at a time, splitting larger moves.
+pub struct Capacity(NonZeroUsize);
+
+impl Capacity {
+    /// `None` for a capacity of 0, which could never finish a move.
+    pub fn new(capacity: usize) -> Option<Self> {
+        NonZeroUsize::new(capacity).map(Capacity)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.0.get()
+    }
+}
+
+impl Crane for Capacity {
+    fn name(&self) -> String {
+        format!("capacity:{}", self.0)
+    }
+
+    fn apply(&mut self, mut stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+        let mut remaining = instruction.amount;
+        while remaining > 0 {
+            let amount = remaining.min(self.0.get());
+            stacks = lift(stacks, instruction.from, instruction.to, amount, true)?;
+            remaining -= amount;
+        }
+        Ok(stacks)
+    }
+}
+
+/// Only reaches neighbouring stacks, so it routes every move through the
+/// stacks in between using the crane it wraps for each hop.
+pub struct Adjacent(Box<dyn Crane>);
+
+impl Adjacent {
+    pub fn new(crane: Box<dyn Crane>) -> Self {
+        Adjacent(crane)
+    }
+}
+
+impl Crane for Adjacent {
+    fn name(&self) -> String {
+        format!("adjacent:{}", self.0.name())
+    }
+
+    fn apply(&mut self, mut stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+        let mut at = instruction.from;
+        while at != instruction.to {
+            let next = if at < instruction.to { at + 1 } else { at - 1 };
+            let hop = Move {
+                amount: instruction.amount,
+                from: at,
+                to: next,
+            };
+            stacks = self.0.apply(stacks, &hop)?;
+            at = next;
+        }
+        Ok(stacks)
+    }
+}
+
+pub const CRANES: &[(&str, &str)] = &[
+    ("9000", "moves crates one at a time"),
+    ("9001", "moves all crates of a move at once"),
+    ("capacity:N", "moves at most N crates at once"),
+    (
+        "adjacent:CRANE",
+        "routes each move through neighbouring stacks using CRANE",
+    ),
+];
+
+/// Builds a crane from a name listed in `CRANES`.
+pub fn crane(name: &str) -> Result<Box<dyn Crane>, String> {
+    match name.split_once(':') {
+        None if name == "9000" => Ok(Box::new(CrateMover9000)),
+        None if name == "9001" => Ok(Box::new(CrateMover9001)),
+        Some(("capacity", capacity)) => match capacity.parse() {
+            Ok(capacity) => Ok(Box::new(Capacity(capacity))),
+            Err(_) => Err(format!(
+                "expected a capacity of at least 1, found {:?}",
+                capacity
+            )),
+        },
+        Some(("adjacent", inner)) => Ok(Box::new(Adjacent(crane(inner)?))),
+        _ => Err(format!(
+            "unknown crane {:?}; expected one of {}",
+            name,
+            CRANES.iter().map(|(name, _)| *name).join(", ")
+        )),
+    }
+}
+
+pub fn crate_mover_9000(stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+    CrateMover9000.apply(stacks, instruction)
+}
+
+pub fn crate_mover_9001(stacks: Stacks, instruction: &Move) -> Result<Stacks, String> {
+    CrateMover9001.apply(stacks, instruction)
 }
 
-fn crate_mover_9001(mut stacks: Stacks, instruction: &Move) -> Stacks {
-    let mut crane = VecDeque::with_capacity(instruction.amount);
-    for _ in 0..instruction.amount {
-        crane.push_front(
-            stacks
-                .get_mut(&instruction.from)
-                .unwrap()
-                .pop_front()
-                .unwrap(),
+/// Rejects moves between stacks missing from the drawing or of more crates
+/// than the source stack holds at that point; every crane moves the same
+/// number of crates, so `heights` does not depend on the crane.
+fn check_move(
+    heights: &mut HashMap<usize, usize>,
+    line: &str,
+    instruction: Move,
+) -> Result<Move, ParseError> {
+    let from_column = line.find(" from ").map_or(1, |i| i + 7);
+    let to_column = line.rfind(" to ").map_or(1, |i| i + 5);
+    let from_height = *heights.get(&instruction.from).ok_or_else(|| {
+        ParseError::new(
+            from_column,
+            instruction.from.to_string(),
+            "a stack from the drawing",
         )
+    })?;
+    if !heights.contains_key(&instruction.to) {
+        return Err(ParseError::new(
+            to_column,
+            instruction.to.to_string(),
+            "a stack from the drawing",
+        ));
     }
-    for c in crane {
-        stacks.get_mut(&instruction.to).unwrap().push_front(c)
+    if instruction.amount > from_height {
+        return Err(ParseError::new(
+            6,
+            instruction.amount.to_string(),
+            format!(
+                "at most {} crates, the height of stack {}",
+                from_height, instruction.from
+            ),
+        ));
     }
-    stacks
+    heights.insert(instruction.from, from_height - instruction.amount);
+    *heights.get_mut(&instruction.to).expect("checked above") += instruction.amount;
+    Ok(instruction)
 }
 
-struct Plan {
+pub struct Plan {
     initial: Stacks,
     moves: Vec<Move>,
 }
 
 impl Plan {
-    fn execute<Crane>(&self, crane: Crane) -> Stacks
-    where
-        Crane: FnMut(Stacks, &Move) -> Stacks,
-    {
-        self.moves.iter().fold(self.initial.clone(), crane)
+    pub fn initial(&self) -> &Stacks {
+        &self.initial
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn execute<C: Crane>(&self, mut crane: C) -> Result<Stacks, String> {
+        self.moves
+            .iter()
+            .try_fold(self.initial.clone(), |stacks, instruction| {
+                crane.apply(stacks, instruction)
+            })
     }
 }
 
 impl FromStr for Plan {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (initial, moves) = s.split_once("\n\n").ok_or(())?;
-        Ok(Plan {
-            initial: parse_initial(initial),
-            moves: moves
-                .lines()
-                .flat_map(|line| Move::from_str(line).ok())
-                .collect(),
-        })
+        Plan::parse(s, &mut Parser::strict(Plan::DAY))
     }
 }
 
-fn tops(stacks: &Stacks) -> String {
-    (1..=(stacks.len()))
-        .map(|index| stacks.get(&index).unwrap().front().unwrap())
+/// The top crate of every stack in order; empty stacks are left out.
+pub fn tops(stacks: &Stacks) -> String {
+    stacks
+        .keys()
+        .sorted()
+        .filter_map(|index| stacks[index].front())
         .join("")
 }
 
-pub fn main() {
-    let plan = Plan::from_str(include_str!("data/day5")).unwrap();
-    let stacks = plan.execute(crate_mover_9000);
-    println!("{}", tops(&stacks));
-    let stacks = plan.execute(crate_mover_9001);
-    println!("{}", tops(&stacks))
+impl Solution for Plan {
+    const DAY: u8 = 5;
+
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        let (initial_text, moves) = input.split_once("\n\n").ok_or_else(|| {
+            parser.fail(
+                ParseError::new(1, "", "a blank line between the drawing and the moves")
+                    .at_line(input.lines().count() + 1),
+            )
+        })?;
+        let initial = parse_initial(initial_text);
+        let mut heights: HashMap<usize, usize> = initial
+            .iter()
+            .map(|(&index, stack)| (index, stack.len()))
+            .collect();
+        let first_line = initial_text.lines().count() + 2;
+        let mut checked = Vec::new();
+        for (index, line) in moves.lines().enumerate() {
+            match Move::from_str(line).and_then(|m| check_move(&mut heights, line, m)) {
+                Ok(instruction) => checked.push(instruction),
+                Err(e) => parser.skip(e.at_line(first_line + index))?,
+            }
+        }
+        Ok(Plan {
+            initial,
+            moves: checked,
+        })
+    }
+
+    fn part1(&self) -> String {
+        tops(
+            &self
+                .execute(CrateMover9000)
+                .expect("moves are checked when parsing"),
+        )
+    }
+
+    fn part2(&self) -> String {
+        tops(
+            &self
+                .execute(CrateMover9001)
+                .expect("moves are checked when parsing"),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -137,14 +422,102 @@ move 1 from 1 to 2";
     #[test]
     fn test_9000() {
         let plan = Plan::from_str(EXAMPLE).unwrap();
-        let stacks = plan.execute(crate_mover_9000);
+        let stacks = plan.execute(crate_mover_9000).unwrap();
         assert_eq!(tops(&stacks), "CMZ");
     }
 
     #[test]
     fn test_9001() {
         let plan = Plan::from_str(EXAMPLE).unwrap();
-        let stacks = plan.execute(crate_mover_9001);
+        let stacks = plan.execute(crate_mover_9001).unwrap();
         assert_eq!(tops(&stacks), "MCD");
     }
+
+    #[test]
+    fn test_capacity() {
+        let plan = Plan::from_str(EXAMPLE).unwrap();
+        let capacity = |n| Capacity::new(n).unwrap();
+        assert_eq!(tops(&plan.execute(capacity(1)).unwrap()), "CMZ");
+        assert_eq!(tops(&plan.execute(capacity(3)).unwrap()), "MCD");
+        let plan =
+            Plan::from_str("[A]    \n[B]    \n[C] [D]\n 1   2 \n\nmove 3 from 1 to 2").unwrap();
+        let stacks = plan.execute(capacity(2)).unwrap();
+        assert!(Capacity::new(0).is_none());
+        assert_eq!(stacks[&2].iter().collect::<String>(), "CABD");
+    }
+
+    #[test]
+    fn test_adjacent() {
+        let plan = Plan::from_str(EXAMPLE).unwrap();
+        assert_eq!(
+            tops(
+                &plan
+                    .execute(Adjacent::new(Box::new(CrateMover9001)))
+                    .unwrap()
+            ),
+            "MCD"
+        );
+        let plan = Plan::from_str(
+            "[A]        \n[B]        \n[C] [D] [E]\n 1   2   3 \n\nmove 2 from 1 to 3",
+        )
+        .unwrap();
+        let stacks = plan
+            .execute(Adjacent::new(Box::new(CrateMover9000)))
+            .unwrap();
+        assert_eq!(stacks[&3].iter().collect::<String>(), "ABE");
+        assert_eq!(stacks[&2].iter().collect::<String>(), "D");
+    }
+
+    #[test]
+    fn test_crane_names() {
+        for name in [
+            "9000",
+            "9001",
+            "capacity:4",
+            "adjacent:9000",
+            "adjacent:capacity:2",
+        ] {
+            assert_eq!(crane(name).unwrap().name(), name);
+        }
+        assert!(crane("9002").is_err());
+        assert!(crane("capacity:0").is_err());
+        let plan = Plan::from_str(EXAMPLE).unwrap();
+        assert_eq!(tops(&plan.execute(crane("9000").unwrap()).unwrap()), "CMZ");
+    }
+
+    #[test]
+    fn test_empty_stacks() {
+        let plan = Plan::from_str("[A]     [C]\n 1   2   3 \n\nmove 1 from 1 to 3").unwrap();
+        assert_eq!(plan.initial()[&2].len(), 0);
+        let stacks = plan.execute(crane("adjacent:9000").unwrap()).unwrap();
+        assert_eq!(stacks[&3].iter().collect::<String>(), "AC");
+        assert_eq!(tops(&stacks), "A");
+        assert!(lift(plan.initial().clone(), 2, 1, 1, false).is_err());
+        assert!(lift(plan.initial().clone(), 1, 4, 1, false).is_err());
+    }
+
+    #[test]
+    fn test_impossible_moves() {
+        let input = EXAMPLE.replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        let error = Plan::from_str(&input).err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (7, 6, "4"));
+        let input = EXAMPLE.replace("move 3 from 1 to 3", "move 3 from 1 to 4");
+        let error = Plan::from_str(&input).err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (7, 18, "4")
+        );
+        let mut parser = Parser::new(5, crate::parse::Mode::Lenient);
+        let plan = Plan::parse(&input, &mut parser).unwrap();
+        assert_eq!(plan.moves().len(), 3);
+        assert_eq!(parser.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_bad_move() {
+        let input = EXAMPLE.replace("move 3 from 1 to 3", "move 3 from one to 3");
+        let error = Plan::from_str(&input).err().unwrap();
+        assert_eq!((error.line, error.column), (7, 13));
+        assert_eq!(error.text, "one");
+    }
 }
@@ -1,27 +1,64 @@
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
 use itertools::Itertools;
 
-fn unique(arr: &[usize]) -> bool {
-    arr.iter().all_unique()
-}
-
-fn find_marker(stream: &[char], size: usize) -> Option<usize> {
+pub fn find_marker(stream: &[char], size: usize) -> Option<usize> {
     stream
         .windows(size)
         .find_position(|w| w.iter().all_unique())
         .map(|(index, _)| index + size)
 }
 
-fn find_start_marker(s: &[char]) -> Option<usize> {
+pub fn find_start_marker(s: &[char]) -> Option<usize> {
     find_marker(s, 4)
 }
-fn find_start_of_message(s: &[char]) -> Option<usize> {
+pub fn find_start_of_message(s: &[char]) -> Option<usize> {
     find_marker(s, 14)
 }
 
-pub fn main() {
-    let input = include_str!("data/day6").chars().collect_vec();
-    println!("{}", find_start_marker(&input).unwrap());
-    println!("{}", find_start_of_message(&input).unwrap());
+pub struct Datastream {
+    start_of_packet: usize,
+    start_of_message: usize,
+}
+
+impl Solution for Datastream {
+    const DAY: u8 = 6;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        let mut lines = input.lines();
+        let mut chars = Vec::new();
+        for (index, c) in lines.next().unwrap_or_default().chars().enumerate() {
+            if c.is_ascii_lowercase() {
+                chars.push(c);
+            } else {
+                parser.skip(ParseError::new(index + 1, c, "a lowercase letter").at_line(1))?;
+            }
+        }
+        for (index, line) in lines.enumerate() {
+            if !line.is_empty() {
+                parser.skip(ParseError::new(1, line, "a single line").at_line(index + 2))?;
+            }
+        }
+        let missing =
+            |expected| parser.fail(ParseError::new(chars.len() + 1, "", expected).at_line(1));
+        Ok(Datastream {
+            start_of_packet: find_start_marker(&chars)
+                .ok_or_else(|| missing("a start-of-packet marker"))?,
+            start_of_message: find_start_of_message(&chars)
+                .ok_or_else(|| missing("a start-of-message marker"))?,
+        })
+    }
+
+    fn part1(&self) -> usize {
+        self.start_of_packet
+    }
+
+    fn part2(&self) -> usize {
+        self.start_of_message
+    }
 }
 
 #[cfg(test)]
@@ -55,4 +92,30 @@ mod tests {
             Some(expected)
         )
     }
+
+    #[test]
+    fn test_bad_characters() {
+        let input = "mjqjpqm1gbljsphdztnvjfqwrcgsmlb\nabc\n";
+        let error = Datastream::parse(input, &mut Parser::strict(6))
+            .err()
+            .unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 8, "1"));
+        let mut parser = Parser::new(6, crate::parse::Mode::Lenient);
+        let stream = Datastream::parse(input, &mut parser).unwrap();
+        assert_eq!(stream.part1(), 7);
+        assert_eq!(parser.warnings().len(), 2);
+    }
+
+    #[test]
+    fn test_missing_markers() {
+        let error = Datastream::parse("abc", &mut Parser::new(6, crate::parse::Mode::Lenient))
+            .err()
+            .unwrap();
+        assert_eq!((error.day, error.column), (6, 4));
+        assert_eq!(error.expected, "a start-of-packet marker");
+        let error = Datastream::parse("abcdabcd", &mut Parser::strict(6))
+            .err()
+            .unwrap();
+        assert_eq!(error.expected, "a start-of-message marker");
+    }
 }
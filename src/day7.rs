@@ -1,74 +1,78 @@
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
 use itertools::Itertools;
 use std::collections::HashMap;
 use traversal::DftPre;
 
 #[derive(Default, Debug)]
-struct Directory {
+pub struct Directory {
     children: HashMap<String, Directory>,
     files: HashMap<String, usize>,
 }
 
 impl Directory {
-    fn add_child(&mut self, name: String) {
+    pub fn add_child(&mut self, name: String) {
         self.children.insert(name, Directory::default());
     }
 
-    fn add_file(&mut self, name: String, size: usize) {
+    pub fn add_file(&mut self, name: String, size: usize) {
         self.files.insert(name, size);
     }
 
-    fn size(&self) -> usize {
+    pub fn size(&self) -> usize {
         self.files.values().sum::<usize>()
             + self.children.values().map(Directory::size).sum::<usize>()
     }
 
-    fn get_child(&mut self, name: &String) -> Option<&mut Directory> {
+    pub fn get_child(&mut self, name: &String) -> Option<&mut Directory> {
         self.children.get_mut(name)
     }
 
-    fn walk(&self) -> impl Iterator<Item = &Directory> {
+    pub fn walk(&self) -> impl Iterator<Item = &Directory> {
         DftPre::new(self, |d| d.children.values()).map(|(_, d)| d)
     }
 }
 
-struct DirectoryWalker<'a, I: Iterator<Item = &'a Directory>> {
-    dir: &'a Directory,
-    children: I,
-}
-
 #[derive(Default, Debug)]
-struct Shell {
+pub struct Shell {
     cwd: Vec<String>,
     fs: Directory,
 }
 
 impl Shell {
-    fn new_with_script(script: &str) -> Self {
+    pub fn new_with_script(script: &str, parser: &mut Parser) -> Result<Self, ParseError> {
         let mut shell = Shell::default();
-        shell.execute(script);
-        shell
+        shell.execute(script, parser)?;
+        Ok(shell)
     }
 
-    fn sum_directories<P: Fn(&Directory) -> bool>(&self, predicate: P) -> usize {
+    pub fn fs(&self) -> &Directory {
+        &self.fs
+    }
+
+    pub fn sum_directories<P: Fn(&Directory) -> bool>(&self, predicate: P) -> usize {
         self.fs
             .walk()
             .fold(0, |acc, d| if predicate(d) { d.size() + acc } else { acc })
     }
 
-    fn execute(&mut self, script: &str) {
-        for line in script.lines() {
-            self.execute_line(line)
+    pub fn execute(&mut self, script: &str, parser: &mut Parser) -> Result<(), ParseError> {
+        for (index, line) in script.lines().enumerate() {
+            if let Err(e) = self.execute_line(line) {
+                parser.skip(e.at_line(index + 1))?;
+            }
         }
+        Ok(())
     }
 
-    fn execute_line(&mut self, line: &str) {
-        match line.chars().next() {
-            Some('$') => self.execute_command(&line[2..]),
-            _ => self.handle_output(&line),
+    fn execute_line(&mut self, line: &str) -> Result<(), ParseError> {
+        match line.strip_prefix("$ ") {
+            Some(command) => self.execute_command(command).map_err(|e| e.shifted(2)),
+            None => self.handle_output(line),
         }
     }
 
-    fn execute_command(&mut self, command: &str) {
+    fn execute_command(&mut self, command: &str) -> Result<(), ParseError> {
         let bits = command.split(' ').collect_vec();
         match bits[..] {
             ["cd", "/"] => {
@@ -78,24 +82,39 @@ impl Shell {
                 self.cwd.pop();
             }
             ["cd", dir] => {
-                self.cwd.push(dir.to_string());
+                let dir = dir.to_string();
+                if self.get_cwd().get_child(&dir).is_none() {
+                    return Err(ParseError::new(4, dir, "a directory listed by ls"));
+                }
+                self.cwd.push(dir);
             }
             ["ls"] => (),
-            _ => panic!("unexpected command {}", command),
+            _ => return Err(ParseError::new(1, command, "cd or ls")),
         };
+        Ok(())
     }
 
-    fn handle_output(&mut self, line: &str) {
+    fn handle_output(&mut self, line: &str) -> Result<(), ParseError> {
         let bits = line.split(' ').collect_vec();
         match bits[..] {
             ["dir", name] => {
                 self.create_directory(name);
             }
             [num, name] if num.chars().all(|c| c.is_ascii_digit()) => {
-                self.add_file(name, num.parse::<usize>().unwrap());
+                let size = num
+                    .parse::<usize>()
+                    .map_err(|_| ParseError::new(1, num, "a file size"))?;
+                self.add_file(name, size);
+            }
+            _ => {
+                return Err(ParseError::new(
+                    1,
+                    line,
+                    "a command, 'dir <name>' or '<size> <name>'",
+                ))
             }
-            _ => panic!("Unexpected output {}", line),
         };
+        Ok(())
     }
 
     fn get_cwd(&mut self) -> &mut Directory {
@@ -115,13 +134,13 @@ impl Shell {
     }
 }
 
-const AVAILABLE_SPACE: usize = 70_000_000;
-const NEEDED_SPACE: usize = 30_000_000;
+pub const AVAILABLE_SPACE: usize = 70_000_000;
+pub const NEEDED_SPACE: usize = 30_000_000;
 
-fn find_smallest_directory_to_delete(fs: &Directory) -> usize {
+pub fn find_smallest_directory_to_delete(fs: &Directory) -> usize {
     let used_space = fs.size();
     let target_space = AVAILABLE_SPACE - NEEDED_SPACE;
-    let required_extra_space = used_space - target_space;
+    let required_extra_space = used_space.saturating_sub(target_space);
     fs.walk()
         .filter_map(|d| {
             let size = d.size();
@@ -132,14 +151,26 @@ fn find_smallest_directory_to_delete(fs: &Directory) -> usize {
             }
         })
         .min()
-        .unwrap()
+        .expect("the root directory is always big enough")
 }
 
-pub fn main() {
-    let script = include_str!("data/day7");
-    let shell = Shell::new_with_script(script);
-    println!("{}", shell.sum_directories(|d| d.size() <= 100_000));
-    println!("{}", find_smallest_directory_to_delete(&shell.fs));
+impl Solution for Shell {
+    const DAY: u8 = 7;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        Shell::new_with_script(input, parser)
+    }
+
+    fn part1(&self) -> usize {
+        self.sum_directories(|d| d.size() <= 100_000)
+    }
+
+    fn part2(&self) -> usize {
+        find_smallest_directory_to_delete(&self.fs)
+    }
 }
 
 #[cfg(test)]
@@ -174,7 +205,9 @@ $ ls
     fn test_sum_small_dirs() {
         assert_eq!(
             95437,
-            Shell::new_with_script(EXAMPLE).sum_directories(|d| d.size() <= 100_000)
+            Shell::new_with_script(EXAMPLE, &mut Parser::strict(7))
+                .unwrap()
+                .sum_directories(|d| d.size() <= 100_000)
         );
     }
 
@@ -182,7 +215,33 @@ $ ls
     fn test_find_smallest_directory_to_delete() {
         assert_eq!(
             24933642,
-            find_smallest_directory_to_delete(&Shell::new_with_script(EXAMPLE).fs)
+            find_smallest_directory_to_delete(
+                &Shell::new_with_script(EXAMPLE, &mut Parser::strict(7))
+                    .unwrap()
+                    .fs
+            )
         )
     }
+
+    #[test]
+    fn test_bad_lines() {
+        let script = "$ cd /\n$ ls\nfoo bar baz\n$ cd nowhere\n$ rm -rf\ndir a\n$ cd a\n$ ls\n10 b";
+        let error = Shell::new_with_script(script, &mut Parser::strict(7))
+            .err()
+            .unwrap();
+        assert_eq!((error.day, error.line, error.column), (7, 3, 1));
+        let mut parser = Parser::new(7, crate::parse::Mode::Lenient);
+        let shell = Shell::new_with_script(script, &mut parser).unwrap();
+        assert_eq!(shell.fs().size(), 10);
+        assert_eq!(shell.part2(), 10);
+        let found = parser
+            .warnings()
+            .iter()
+            .map(|w| (w.line, w.column, w.text.as_str()))
+            .collect_vec();
+        assert_eq!(
+            found,
+            [(3, 1, "foo bar baz"), (4, 6, "nowhere"), (5, 3, "rm -rf")]
+        );
+    }
 }
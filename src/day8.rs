@@ -1,12 +1,24 @@
+use crate::parse::{ParseError, Parser};
+use crate::solution::Solution;
 use itertools::Itertools;
 use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone)]
-struct Coordinates {
+pub struct Coordinates {
     x: usize,
     y: usize,
 }
 
+impl Coordinates {
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+}
+
 impl From<(usize, usize)> for Coordinates {
     fn from(coords: (usize, usize)) -> Self {
         Self {
@@ -17,38 +29,46 @@ impl From<(usize, usize)> for Coordinates {
 }
 
 #[derive(Debug)]
-struct Tree {
+pub struct Tree {
     coords: Coordinates,
     height: TreeHeight,
 }
 
 impl Tree {
-    fn top<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
+    pub fn coords(&self) -> Coordinates {
+        self.coords
+    }
+
+    pub fn height(&self) -> TreeHeight {
+        self.height
+    }
+
+    pub fn top<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
         (0..self.coords.y)
             .rev()
             .map(|y| (self.coords.x, y))
             .flat_map(|c| map.get_tree(c))
     }
-    fn right<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
+    pub fn right<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
         ((self.coords.x + 1)..map.width)
             .map(|x| (x, self.coords.y))
             .flat_map(|c| map.get_tree(c))
     }
 
-    fn down<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
+    pub fn down<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
         ((self.coords.y + 1)..map.height)
             .map(|y| (self.coords.x, y))
             .flat_map(|c| map.get_tree(c))
     }
 
-    fn left<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
+    pub fn left<'a>(&'a self, map: &'a Map) -> impl Iterator<Item = Tree> + 'a {
         (0..self.coords.x)
             .rev()
             .map(|x| (x, self.coords.y))
             .flat_map(|c| map.get_tree(c))
     }
 
-    fn visible(&self, map: &Map) -> bool {
+    pub fn visible(&self, map: &Map) -> bool {
         let los = |tree: Tree| tree.height < self.height;
         self.coords.x == 0
             || self.coords.y == 0
@@ -60,7 +80,7 @@ impl Tree {
             || self.left(map).all(los)
     }
 
-    fn scenic_score(&self, map: &Map) -> usize {
+    pub fn scenic_score(&self, map: &Map) -> usize {
         let visible = |tree: Tree| tree.height >= self.height;
         let add_one: fn(usize) -> usize = |n| n + 1;
         self.top(map)
@@ -86,53 +106,76 @@ impl Tree {
 }
 
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     width: usize,
     height: usize,
     trees: Vec<TreeHeight>,
 }
 
 impl Map {
-    fn get_tree<C: Into<Coordinates>>(&self, at: C) -> Option<Tree> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_tree<C: Into<Coordinates>>(&self, at: C) -> Option<Tree> {
         let coords: Coordinates = at.into();
         self.trees
             .get((coords.y * self.width) + coords.x)
             .map(|height| Tree {
-                coords: coords,
+                coords,
                 height: *height,
             })
     }
 
-    fn iter(&self) -> impl Iterator<Item = Tree> + '_ {
+    pub fn iter(&self) -> impl Iterator<Item = Tree> + '_ {
         (0..self.width)
             .cartesian_product(0..self.height)
             .flat_map(|(x, y)| self.get_tree((x, y)))
     }
 
-    fn visible_trees(&self) -> usize {
+    pub fn visible_trees(&self) -> usize {
         self.iter().filter(|tree| tree.visible(self)).count()
     }
 
-    fn most_scenic_tree(&self) -> usize {
+    pub fn most_scenic_tree(&self) -> usize {
         self.iter()
-            .map(|tree| tree.scenic_score(&self))
+            .map(|tree| tree.scenic_score(self))
             .max()
             .expect("empty map")
     }
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines().peekable();
-        let width = lines.peek().map(|line| line.len()).ok_or(())?;
-        let trees = lines
-            .flat_map(|line| {
-                line.chars()
-                    .flat_map(|c| c.to_digit(10).and_then(|d| TreeHeight::try_from(d).ok()))
-            })
-            .collect_vec();
+        let width = s
+            .lines()
+            .next()
+            .map(|line| line.chars().count())
+            .filter(|width| *width > 0)
+            .ok_or_else(|| ParseError::new(1, "", "a row of tree heights").at_line(1))?;
+        let mut trees = Vec::with_capacity(s.len());
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let height = c
+                    .to_digit(10)
+                    .and_then(|d| TreeHeight::try_from(d).ok())
+                    .ok_or_else(|| {
+                        ParseError::new(x + 1, c, "a tree height from 0 to 9").at_line(y + 1)
+                    })?;
+                trees.push(height);
+            }
+            if line.chars().count() != width {
+                return Err(
+                    ParseError::new(1, line, format!("a row of {} trees", width)).at_line(y + 1),
+                );
+            }
+        }
         let height = trees.len() / width;
         Ok(Self {
             width,
@@ -142,12 +185,25 @@ impl FromStr for Map {
     }
 }
 
-type TreeHeight = u8;
+pub type TreeHeight = u8;
+
+impl Solution for Map {
+    const DAY: u8 = 8;
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError> {
+        Map::from_str(input).map_err(|e| parser.fail(e))
+    }
+
+    fn part1(&self) -> usize {
+        self.visible_trees()
+    }
 
-pub fn main() {
-    let map = Map::from_str(include_str!("data/day8")).unwrap();
-    println!("{}", map.visible_trees());
-    println!("{}", map.most_scenic_tree());
+    fn part2(&self) -> usize {
+        self.most_scenic_tree()
+    }
 }
 
 #[cfg(test)]
@@ -170,4 +226,11 @@ mod tests {
         let map = Map::from_str(EXAMPLE).unwrap();
         assert_eq!(8, map.most_scenic_tree());
     }
+
+    #[test]
+    fn test_bad_tree() {
+        let error = Map::from_str("30373\n255x2").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "a tree height from 0 to 9");
+    }
 }
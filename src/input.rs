@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Source {
+    #[default]
+    Default,
+    Stdin,
+    File(PathBuf),
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err("empty input path".to_string()),
+            "-" => Ok(Source::Stdin),
+            path => Ok(Source::File(PathBuf::from(path))),
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::Stdin => write!(f, "-"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+pub fn data_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("data")
+}
+
+pub fn default_path(day: u8) -> PathBuf {
+    data_dir().join(format!("day{}", day))
+}
+
+#[cfg(feature = "embedded-inputs")]
+fn embedded(day: u8) -> Option<&'static str> {
+    match day {
+        1 => Some(include_str!("data/day1")),
+        2 => Some(include_str!("data/day2")),
+        3 => Some(include_str!("data/day3")),
+        4 => Some(include_str!("data/day4")),
+        5 => Some(include_str!("data/day5")),
+        6 => Some(include_str!("data/day6")),
+        7 => Some(include_str!("data/day7")),
+        8 => Some(include_str!("data/day8")),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+fn embedded(_day: u8) -> Option<&'static str> {
+    None
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))
+}
+
+pub fn load(day: u8, source: &Source) -> Result<String, String> {
+    load_from(day, source, &default_path(day))
+}
+
+/// Like [`load`], but reads [`Source::Default`] from `default` instead of
+/// `dayN` in the data directory.
+pub fn load_from(day: u8, source: &Source, default: &Path) -> Result<String, String> {
+    match source {
+        Source::Default => match (read_file(default), embedded(day)) {
+            (Ok(input), _) => Ok(input),
+            (Err(_), Some(input)) => Ok(input.to_string()),
+            (Err(e), None) => Err(e),
+        },
+        Source::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("cannot read stdin: {}", e))?;
+            Ok(input)
+        }
+        Source::File(path) => read_file(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_from_str() {
+        assert_eq!(Source::from_str("-"), Ok(Source::Stdin));
+        assert_eq!(
+            Source::from_str("inputs/day1.txt"),
+            Ok(Source::File(PathBuf::from("inputs/day1.txt")))
+        );
+        assert!(Source::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_load_default() {
+        let input = load(1, &Source::Default).unwrap();
+        assert_eq!(input, fs::read_to_string(default_path(1)).unwrap());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let source = Source::File(data_dir().join("day0"));
+        assert!(load(0, &source).unwrap_err().contains("day0"));
+    }
+}
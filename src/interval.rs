@@ -0,0 +1,316 @@
+use std::fmt::{Display, Formatter};
+
+/// An inclusive, non-empty range of section IDs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    /// Returns `None` when `end` is below `start`.
+    pub fn new(start: u64, end: u64) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn single(section: u64) -> Self {
+        Interval {
+            start: section,
+            end: section,
+        }
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// The number of sections, saturating for the full `u64` range.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        (self.end - self.start).saturating_add(1)
+    }
+
+    pub fn contains(&self, section: u64) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    pub fn covers(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The combined interval, if the two overlap or are adjacent.
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        let touching = self.overlaps(other)
+            || self.end.checked_add(1) == Some(other.start)
+            || other.end.checked_add(1) == Some(self.start);
+        touching.then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Disjoint, non-adjacent intervals kept in ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of sections in the set, saturating at `u64::MAX`.
+    pub fn coverage(&self) -> u64 {
+        self.intervals.iter().fold(0, |total: u64, interval| {
+            total.saturating_add(interval.len())
+        })
+    }
+
+    pub fn contains(&self, section: u64) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end < section);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(section))
+    }
+
+    /// Adds `interval`, merging it with every interval it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval) {
+        let first = self
+            .intervals
+            .partition_point(|other| other.end.saturating_add(1) < interval.start);
+        let mut merged = interval;
+        let mut last = first;
+        while let Some(union) = self
+            .intervals
+            .get(last)
+            .and_then(|other| merged.union(other))
+        {
+            merged = union;
+            last += 1;
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Removes every section of `interval` from the set.
+    pub fn remove(&mut self, interval: Interval) {
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end);
+        if first >= last {
+            return;
+        }
+        let left = (self.intervals[first].start < interval.start)
+            .then(|| Interval::new(self.intervals[first].start, interval.start - 1))
+            .flatten();
+        let right = (self.intervals[last - 1].end > interval.end)
+            .then(|| Interval::new(interval.end + 1, self.intervals[last - 1].end))
+            .flatten();
+        self.intervals
+            .splice(first..last, left.into_iter().chain(right));
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        union.extend(other.intervals.iter().copied());
+        union
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut difference = self.clone();
+        for &interval in &other.intervals {
+            difference.remove(interval);
+        }
+        difference
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = Vec::new();
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            intervals.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The sections of `bounds` that are not in the set.
+    pub fn complement(&self, bounds: Interval) -> IntervalSet {
+        let mut complement = IntervalSet::from_iter([bounds]);
+        for &interval in &self.intervals {
+            complement.remove(interval);
+        }
+        complement
+    }
+
+    /// The holes between the first and last interval of the set.
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.intervals
+            .windows(2)
+            .filter_map(|pair| Interval::new(pair[0].end + 1, pair[1].start - 1))
+            .collect()
+    }
+
+    /// The smallest interval containing the whole set.
+    pub fn hull(&self) -> Option<Interval> {
+        Some(Interval {
+            start: self.intervals.first()?.start,
+            end: self.intervals.last()?.end,
+        })
+    }
+}
+
+impl Extend<Interval> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Interval>>(&mut self, intervals: I) {
+        for interval in intervals {
+            self.insert(interval);
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(intervals);
+        set
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, interval) in self.intervals.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", interval)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: u64, end: u64) -> Interval {
+        Interval::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = interval(2, 6);
+        let b = interval(4, 8);
+        assert_eq!(a.len(), 5);
+        assert!(a.contains(2) && a.contains(6) && !a.contains(7));
+        assert!(a.overlaps(&b) && !a.covers(&b));
+        assert!(interval(2, 8).covers(&interval(3, 7)));
+        assert_eq!(a.intersection(&b), Some(interval(4, 6)));
+        assert_eq!(a.union(&b), Some(interval(2, 8)));
+        assert_eq!(Interval::new(5, 4), None);
+        assert_eq!(a.to_string(), "2-6");
+    }
+
+    #[test]
+    fn test_disjoint() {
+        let a = interval(2, 4);
+        assert!(!a.overlaps(&interval(6, 8)));
+        assert_eq!(a.intersection(&interval(6, 8)), None);
+        assert_eq!(a.union(&interval(6, 8)), None);
+        assert_eq!(a.union(&interval(5, 8)), Some(interval(2, 8)));
+    }
+
+    #[test]
+    fn test_large_sections() {
+        let full = interval(0, u64::MAX);
+        assert_eq!(full.len(), u64::MAX);
+        assert!(full.covers(&interval(u64::MAX, u64::MAX)));
+        assert_eq!(interval(u64::MAX - 1, u64::MAX).len(), 2);
+        assert_eq!(
+            interval(u64::MAX, u64::MAX).union(&interval(0, u64::MAX - 1)),
+            Some(full)
+        );
+    }
+
+    fn set(intervals: &[(u64, u64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| interval(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut intervals = set(&[(10, 12), (1, 3), (6, 7)]);
+        assert_eq!(intervals, set(&[(1, 3), (6, 7), (10, 12)]));
+        intervals.insert(interval(4, 5));
+        assert_eq!(intervals, set(&[(1, 7), (10, 12)]));
+        intervals.insert(interval(0, 20));
+        assert_eq!(intervals, set(&[(0, 20)]));
+        assert_eq!(intervals.coverage(), 21);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut intervals = set(&[(1, 10), (20, 30)]);
+        intervals.remove(interval(5, 22));
+        assert_eq!(intervals, set(&[(1, 4), (23, 30)]));
+        intervals.remove(interval(0, 1));
+        intervals.remove(interval(30, 40));
+        assert_eq!(intervals, set(&[(2, 4), (23, 29)]));
+        intervals.remove(interval(12, 15));
+        assert_eq!(intervals.coverage(), 10);
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = set(&[(1, 5), (10, 15)]);
+        let b = set(&[(4, 11), (20, 21)]);
+        assert_eq!(a.union(&b), set(&[(1, 15), (20, 21)]));
+        assert_eq!(a.intersection(&b), set(&[(4, 5), (10, 11)]));
+        assert_eq!(a.difference(&b), set(&[(1, 3), (12, 15)]));
+        assert_eq!(
+            a.complement(interval(0, 20)),
+            set(&[(0, 0), (6, 9), (16, 20)])
+        );
+        assert_eq!(a.gaps(), [interval(6, 9)]);
+        assert_eq!(a.hull(), Some(interval(1, 15)));
+        assert!(a.contains(10) && !a.contains(7) && !a.contains(16));
+        assert_eq!(a.to_string(), "1-5, 10-15");
+    }
+}
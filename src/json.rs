@@ -0,0 +1,84 @@
+use std::fmt::{Display, Formatter, Write};
+
+pub fn string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub fn array<I: IntoIterator<Item = String>>(values: I) -> String {
+    format!("[{}]", values.into_iter().collect::<Vec<_>>().join(","))
+}
+
+#[derive(Debug, Default)]
+pub struct Object {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Object::default()
+    }
+
+    pub fn number<N: Display>(self, key: &'static str, value: N) -> Self {
+        self.raw(key, value.to_string())
+    }
+
+    pub fn string(self, key: &'static str, value: &str) -> Self {
+        self.raw(key, string(value))
+    }
+
+    pub fn raw(mut self, key: &'static str, value: String) -> Self {
+        self.fields.push((key, value));
+        self
+    }
+}
+
+impl Display for Object {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char('{')?;
+        for (index, (key, value)) in self.fields.iter().enumerate() {
+            if index > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{}:{}", string(key), value)?;
+        }
+        f.write_char('}')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        assert_eq!(string("plain"), "\"plain\"");
+        assert_eq!(string("a \"b\"\n\\"), "\"a \\\"b\\\"\\n\\\\\"");
+        assert_eq!(string("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn test_object() {
+        let object = Object::new()
+            .number("day", 1)
+            .string("answer", "CMZ")
+            .raw("warnings", array(vec![string("x")]));
+        assert_eq!(
+            object.to_string(),
+            "{\"day\":1,\"answer\":\"CMZ\",\"warnings\":[\"x\"]}"
+        );
+    }
+}
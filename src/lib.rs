@@ -0,0 +1,31 @@
+//! Advent of Code 2022 solutions and the tooling around them.
+//!
+//! Each `dayN` module exposes its puzzle model and implements [`solution::Solution`];
+//! [`registry::DAYS`] lists them for the command-line runner. The modules that
+//! only serve the runner (inputs, answers, benchmarks, scaffolding) are behind
+//! the default `cli` feature.
+
+#[cfg(feature = "cli")]
+pub mod answers;
+#[cfg(feature = "cli")]
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+#[cfg(feature = "cli")]
+pub mod input;
+pub mod interval;
+#[cfg(feature = "cli")]
+pub mod json;
+#[cfg(feature = "cli")]
+pub mod manifest;
+pub mod parse;
+pub mod registry;
+#[cfg(feature = "cli")]
+pub mod scaffold;
+pub mod solution;
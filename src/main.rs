@@ -1,15 +1,843 @@
-#![allow(dead_code)]
-extern crate core;
-
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-
-fn main() {
-    day8::main();
+use aoc2022::bench::Baseline;
+use aoc2022::day1;
+use aoc2022::day2::optimize::{Goal, Limit};
+use aoc2022::day2::{self, rules::Ruleset, Outcome};
+use aoc2022::day3;
+use aoc2022::day4;
+use aoc2022::day5;
+use aoc2022::input::Source;
+use aoc2022::interval::Interval;
+use aoc2022::json::Object;
+use aoc2022::parse::{self, Mode, ParseError};
+use aoc2022::registry::{Part, Selection};
+use aoc2022::solution::Solution;
+use aoc2022::{answers, bench, input, json, manifest, registry, scaffold};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solver for a day, or for every day with `all`
+    Run {
+        day: Selection,
+        /// Only run this part (1 or 2)
+        #[arg(long)]
+        part: Option<Part>,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short)]
+        input: Option<Source>,
+        /// Fail on the first malformed input line instead of skipping it
+        #[arg(long)]
+        strict: bool,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// List the available days
+    List,
+    /// Check every solver against the recorded answers
+    Verify {
+        #[arg(default_value = "all")]
+        day: Selection,
+        /// Answers file to check against
+        #[arg(long)]
+        answers: Option<PathBuf>,
+    },
+    /// Print manifest entries for the checked-in inputs
+    Manifest,
+    /// Time parsing and both parts over repeated runs
+    Bench {
+        #[arg(default_value = "all")]
+        day: Selection,
+        /// Number of runs per day
+        #[arg(long, default_value_t = 100)]
+        runs: usize,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short)]
+        input: Option<Source>,
+        /// Save the median timings to this file
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Compare against timings previously saved with --save
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Day 1 tools
+    Day1 {
+        #[command(subcommand)]
+        command: Day1Command,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short, global = true)]
+        input: Option<Source>,
+    },
+    /// Day 2 tools
+    Day2 {
+        #[command(subcommand)]
+        command: Day2Command,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short, global = true)]
+        input: Option<Source>,
+        /// Game rules file; defaults to Rock Paper Scissors
+        #[arg(long, global = true)]
+        rules: Option<PathBuf>,
+    },
+    /// Day 3 tools
+    Day3 {
+        #[command(subcommand)]
+        command: Day3Command,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short, global = true)]
+        input: Option<Source>,
+    },
+    /// Day 4 tools
+    Day4 {
+        #[command(subcommand)]
+        command: Day4Command,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short, global = true)]
+        input: Option<Source>,
+    },
+    /// Day 5 tools
+    Day5 {
+        #[command(subcommand)]
+        command: Day5Command,
+        /// Read the puzzle input from this file, or from stdin with `-`
+        #[arg(long, short, global = true)]
+        input: Option<Source>,
+    },
+    /// Generate and register the module for a new day
+    New {
+        day: u8,
+        /// Puzzle title shown by `list`
+        #[arg(long)]
+        title: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum Day1Command {
+    /// Show the elves carrying the most calories
+    Top {
+        /// Number of elves to show
+        #[arg(short, default_value_t = 3)]
+        n: usize,
+    },
+    /// Summarise the calorie totals with a histogram
+    Stats {
+        /// Number of histogram buckets
+        #[arg(long, default_value_t = 10)]
+        buckets: usize,
+        /// Width of the longest histogram bar
+        #[arg(long, default_value_t = 40)]
+        width: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum Day2Command {
+    /// Score the guide under both readings of the second column
+    Play,
+    /// Play the guide round by round and summarise the results
+    Simulate {
+        /// Read the second column as your shape (1) or as the outcome (2)
+        #[arg(long, default_value = "1")]
+        part: Part,
+        /// Write the per-round breakdown as CSV to this file, or to stdout with `-`
+        #[arg(long)]
+        csv: Option<PathBuf>,
+    },
+    /// Write a strategy for the opponent's moves that beats a target score
+    Optimize {
+        /// The strategy has to score more than this
+        #[arg(long)]
+        target: u32,
+        /// Outcome to have as few rounds of as possible (win, draw or loss)
+        #[arg(long, value_parser = day2::optimize::outcome_name)]
+        minimise: Outcome,
+        /// Cap an outcome at a share of the rounds, e.g. `win=30%`
+        #[arg(long)]
+        at_most: Vec<Limit>,
+        /// Write the strategy to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Score every mapping of the second column to shapes and to outcomes
+    Infer {
+        /// List every mapping instead of only the best and worst
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum Day3Command {
+    /// Print a random rucksack list, e.g. for benchmarking
+    Generate {
+        /// Number of groups of three rucksacks
+        #[arg(long, default_value_t = 100_000)]
+        groups: usize,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+    /// List the items each group of rucksacks has in common
+    Groups {
+        /// Number of equally sized compartments per rucksack
+        #[arg(long, default_value_t = 2)]
+        compartments: usize,
+        /// Number of consecutive rucksacks per group
+        #[arg(long, default_value_t = 3)]
+        group_size: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum Day4Command {
+    /// Report which sections are covered by nobody and by several elves
+    Coverage {
+        /// First section to consider; defaults to the lowest assigned section
+        #[arg(long)]
+        from: Option<u64>,
+        /// Last section to consider; defaults to the highest assigned section
+        #[arg(long)]
+        to: Option<u64>,
+    },
+    /// Draw each pair as section strips with overlaps highlighted
+    Render {
+        /// Scale the sections to fit the terminal width
+        #[arg(long)]
+        compress: bool,
+        /// Width to compress to; defaults to $COLUMNS or 80
+        #[arg(long)]
+        width: Option<usize>,
+        /// Highlight assignments and overlaps with ANSI colours
+        #[arg(long)]
+        colour: bool,
+        /// First section to draw; defaults to 1
+        #[arg(long)]
+        from: Option<u64>,
+        /// Last section to draw; defaults to the highest assigned section
+        #[arg(long)]
+        to: Option<u64>,
+    },
+    /// Find the busiest sections and the lines whose ranges overlap
+    Sweep {
+        /// List every overlapping pair of lines instead of only counting them
+        #[arg(long)]
+        pairs: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum Day5Command {
+    /// List the available crane models
+    Cranes,
+    /// Run the rearrangement with a crane model and print the top crates
+    Run {
+        /// Crane model, e.g. `9001` or `capacity:3`; see `day5 cranes`
+        #[arg(long, default_value = "9000")]
+        crane: String,
+    },
+}
+
+fn warning_json(warning: &ParseError) -> String {
+    Object::new()
+        .number("line", warning.line)
+        .number("column", warning.column)
+        .string("text", &warning.text)
+        .string("expected", &warning.expected)
+        .to_string()
+}
+
+fn run(
+    selection: Selection,
+    part: Option<Part>,
+    source: Option<Source>,
+    mode: Mode,
+    format: Format,
+) -> Result<(), String> {
+    if selection == Selection::All && source.is_some() {
+        return Err("--input can only be used with a single day".to_string());
+    }
+    let source = source.unwrap_or_default();
+    let manifest = manifest::load_default()?;
+    let parts = part.map(|p| vec![p]).unwrap_or_else(|| Part::ALL.to_vec());
+    for day in selection.days()? {
+        let input = manifest.load(day.number(), &source)?;
+        let solved = day.solve(&input, &parts, mode).map_err(|e| e.to_string())?;
+        if format == Format::Json {
+            let warnings = json::array(solved.warnings().iter().map(warning_json));
+            for answer in solved.answers() {
+                let object = Object::new()
+                    .number("day", day.number())
+                    .number("part", answer.part())
+                    .string("answer", answer.value())
+                    .number("parse_ns", solved.parse_elapsed().as_nanos())
+                    .number("elapsed_ns", answer.elapsed().as_nanos())
+                    .string("input", &manifest.describe(day.number(), &source))
+                    .raw("warnings", warnings.clone());
+                println!("{}", object);
+            }
+            continue;
+        }
+        for warning in solved.warnings() {
+            eprintln!("warning: {}", warning);
+        }
+        for answer in solved.answers() {
+            println!(
+                "day {} part {}: {}",
+                day.number(),
+                answer.part(),
+                answer.value()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn list() {
+    for day in registry::DAYS {
+        println!("{:>2} {}", day.number(), day.title());
+    }
+}
+
+fn verify(selection: Selection, answers: Option<PathBuf>) -> Result<(), String> {
+    let answers = answers::load(&answers.unwrap_or_else(answers::default_path))?;
+    let manifest = manifest::load_default()?;
+    let mut failures = 0;
+    for day in selection.days()? {
+        // Freshly scaffolded days have no input or answers yet.
+        if !answers.knows(day.number()) {
+            println!("day {}: skipped, no known answers", day.number());
+            continue;
+        }
+        let solved = manifest
+            .load(day.number(), &Source::Default)
+            .and_then(|input| {
+                day.solve(&input, &Part::ALL, Mode::Strict)
+                    .map_err(|e| e.to_string())
+            });
+        let solved = match solved {
+            Ok(solved) => solved,
+            Err(e) => {
+                println!("day {}: ERROR {}", day.number(), e);
+                failures += 1;
+                continue;
+            }
+        };
+        for answer in solved.answers() {
+            let verdict = answers.check(day.number(), answer.part(), answer.value());
+            if verdict.is_failure() {
+                failures += 1;
+            }
+            println!(
+                "day {} part {}: {} {}",
+                day.number(),
+                answer.part(),
+                answer.value(),
+                verdict
+            );
+        }
+    }
+    match failures {
+        0 => Ok(()),
+        n => Err(format!("{} check(s) failed", n)),
+    }
+}
+
+fn run_bench(
+    selection: Selection,
+    runs: usize,
+    source: Option<Source>,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    format: Format,
+) -> Result<(), String> {
+    if selection == Selection::All && source.is_some() {
+        return Err("--input can only be used with a single day".to_string());
+    }
+    if runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+    let source = source.unwrap_or_default();
+    let manifest = manifest::load_default()?;
+    let baseline = baseline.map(|path| Baseline::load(&path)).transpose()?;
+    let mut measurements = Vec::new();
+    for day in selection.days()? {
+        let input = manifest.load(day.number(), &source)?;
+        measurements.extend(bench::bench(day, &input, runs).map_err(|e| e.to_string())?);
+    }
+    for m in &measurements {
+        let change = baseline.as_ref().and_then(|baseline| baseline.change(m));
+        if format == Format::Json {
+            let object = Object::new()
+                .number("day", m.day)
+                .string("stage", &m.stage.to_string())
+                .number("runs", runs)
+                .number("min_ns", m.stats.min.as_nanos())
+                .number("median_ns", m.stats.median.as_nanos())
+                .number("max_ns", m.stats.max.as_nanos())
+                .string("input", &manifest.describe(m.day, &source));
+            match change {
+                Some(change) => println!("{}", object.number("change_percent", change)),
+                None => println!("{}", object),
+            }
+            continue;
+        }
+        let change = change
+            .map(|change| format!("  {:+.1}%", change))
+            .unwrap_or_default();
+        println!(
+            "day {} {:<5}  min {:>10.2?}  median {:>10.2?}  max {:>10.2?}{}",
+            m.day, m.stage, m.stats.min, m.stats.median, m.stats.max, change
+        );
+    }
+    if let Some(path) = save {
+        Baseline::from_measurements(&measurements).save(&path)?;
+    }
+    Ok(())
+}
+
+fn print_manifest() -> Result<(), String> {
+    let manifest = manifest::load_default()?;
+    println!("# day file lines fnv1a-64");
+    for day in registry::DAYS {
+        let file = manifest
+            .get(day.number())
+            .map(|entry| entry.file.clone())
+            .unwrap_or_else(|| format!("day{}", day.number()));
+        let input = input::load_from(
+            day.number(),
+            &Source::Default,
+            &manifest.input_path(day.number()),
+        )?;
+        println!(
+            "{}",
+            manifest::Entry::for_input(day.number(), &file, &input)
+        );
+    }
+    Ok(())
+}
+
+fn day1(command: Day1Command, source: Option<Source>) -> Result<(), String> {
+    let source = source.unwrap_or_default();
+    let manifest = manifest::load_default()?;
+    let input = manifest.load(1, &source)?;
+    let inventory = day1::Inventory::parse(&input, &mut parse::Parser::new(1, Mode::Lenient))
+        .map_err(|e| e.to_string())?;
+    match command {
+        Day1Command::Top { n } => {
+            let top = day1::top_elves(inventory.elves(), n);
+            for (rank, (index, total, items)) in top.entries().into_iter().enumerate() {
+                println!(
+                    "#{} elf {}: {} calories ({} items)",
+                    rank + 1,
+                    index,
+                    total,
+                    items.len()
+                );
+            }
+            if !top.tied().is_empty() {
+                println!(
+                    "tied with #{}: {}",
+                    top.top().len(),
+                    top.tied()
+                        .iter()
+                        .map(|elf| format!("elf {}", elf.index()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            println!("total: {}", top.total());
+        }
+        Day1Command::Stats { buckets, width } => {
+            let stats = day1::statistics(inventory.elves()).ok_or("there are no elves")?;
+            println!("{}", stats);
+            println!();
+            println!("{}", day1::histogram(inventory.elves(), buckets, width));
+        }
+    }
+    Ok(())
+}
+
+fn load_rules(path: Option<PathBuf>) -> Result<Ruleset, String> {
+    match path {
+        None => Ok(Ruleset::default()),
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+            .parse()
+            .map_err(|e: ParseError| format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn day2(
+    command: Day2Command,
+    source: Option<Source>,
+    rules: Option<PathBuf>,
+) -> Result<(), String> {
+    let source = source.unwrap_or_default();
+    let manifest = manifest::load_default()?;
+    let input = manifest.load(2, &source)?;
+    let rules = load_rules(rules)?;
+    let mut parser = parse::Parser::new(2, Mode::Lenient);
+    match command {
+        Day2Command::Play => {
+            let matches = rules
+                .matches(&input, &mut parser)
+                .map_err(|e| e.to_string())?;
+            let strategies = rules
+                .strategies(&input, &mut parser)
+                .map_err(|e| e.to_string())?;
+            let score = rules.score_matches(&matches);
+            let solved = rules.score_strategies(&strategies);
+            println!("part 1 (second column is your shape): {}", score);
+            println!("part 2 (second column is the outcome): {}", solved);
+        }
+        Day2Command::Simulate { part, csv } => {
+            let matches = match part {
+                Part::One => rules.matches(&input, &mut parser),
+                Part::Two => rules.strategies(&input, &mut parser).map(|strategies| {
+                    strategies
+                        .iter()
+                        .filter_map(|strategy| rules.resolve(strategy))
+                        .collect()
+                }),
+            }
+            .map_err(|e| e.to_string())?;
+            let tournament = day2::tournament::simulate(&rules, &matches);
+            match csv {
+                Some(path) if path.as_os_str() == "-" => tournament
+                    .write_csv(&rules, std::io::stdout().lock())
+                    .map_err(|e| format!("cannot write CSV: {}", e))?,
+                Some(path) => {
+                    let file = std::fs::File::create(&path)
+                        .map_err(|e| format!("cannot create {}: {}", path.display(), e))?;
+                    tournament
+                        .write_csv(&rules, std::io::BufWriter::new(file))
+                        .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+                    println!("{}", tournament);
+                }
+                None => println!("{}", tournament),
+            }
+        }
+        Day2Command::Optimize {
+            target,
+            minimise,
+            at_most,
+            output,
+        } => {
+            let strategies = rules
+                .strategies(&input, &mut parser)
+                .map_err(|e| e.to_string())?;
+            let opponents: Vec<_> = strategies
+                .iter()
+                .map(|strategy| strategy.opponent())
+                .collect();
+            let goal = Goal {
+                target,
+                minimise,
+                limits: at_most,
+            };
+            let strategies = day2::optimize::optimize(&rules, &opponents, &goal)?;
+            let matches: Vec<_> = strategies
+                .iter()
+                .filter_map(|strategy| rules.resolve(strategy))
+                .collect();
+            let summary = day2::tournament::simulate(&rules, &matches);
+            match output {
+                Some(path) => {
+                    let file = std::fs::File::create(&path)
+                        .map_err(|e| format!("cannot create {}: {}", path.display(), e))?;
+                    day2::optimize::write_strategies(
+                        &rules,
+                        &strategies,
+                        std::io::BufWriter::new(file),
+                    )
+                    .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+                    println!("{}", summary);
+                }
+                None => {
+                    day2::optimize::write_strategies(&rules, &strategies, std::io::stdout().lock())
+                        .map_err(|e| format!("cannot write strategy: {}", e))?;
+                    eprintln!("{}", summary);
+                }
+            }
+        }
+        Day2Command::Infer { all } => {
+            let inference =
+                day2::infer::infer(&rules, &input, &mut parser).map_err(|e| e.to_string())?;
+            for (reading, mappings) in [
+                ("shapes", inference.shapes()),
+                ("outcomes", inference.outcomes()),
+            ] {
+                println!(
+                    "second column as {} ({} mappings):",
+                    reading,
+                    mappings.len()
+                );
+                let shown: Vec<(&str, &day2::infer::Mapping)> = if all {
+                    mappings.iter().map(|mapping| ("", mapping)).collect()
+                } else {
+                    mappings
+                        .first()
+                        .map(|mapping| ("best", mapping))
+                        .into_iter()
+                        .chain(mappings.last().map(|mapping| ("worst", mapping)))
+                        .collect()
+                };
+                for (label, mapping) in shown {
+                    println!(
+                        "  {:<5} {:>8}  {}",
+                        label,
+                        mapping.total(),
+                        mapping.describe(&rules)
+                    );
+                }
+            }
+        }
+    }
+    let mut warnings = parser.into_warnings();
+    warnings.sort_by_key(|warning| warning.line);
+    warnings.dedup_by_key(|warning| warning.line);
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+    Ok(())
+}
+
+fn day3(command: Day3Command, source: Option<Source>) -> Result<(), String> {
+    match command {
+        Day3Command::Generate { groups, seed } => {
+            print!("{}", day3::generate::rucksacks(groups, seed));
+        }
+        Day3Command::Groups {
+            compartments,
+            group_size,
+        } => {
+            if compartments == 0 || group_size == 0 {
+                return Err("compartments and group size must be at least 1".to_string());
+            }
+            let source = source.unwrap_or_default();
+            let manifest = manifest::load_default()?;
+            let input = manifest.load(3, &source)?;
+            let layout = day3::Layout {
+                compartments,
+                group_size,
+            };
+            let mut parser = parse::Parser::new(3, Mode::Lenient);
+            let rucksacks = day3::Rucksacks::with_layout(&input, layout, &mut parser)
+                .map_err(|e| e.to_string())?;
+            let describe = |items: day3::items::ItemSet| {
+                let priority: u32 = items.iter().map(u32::from).sum();
+                let items: String = items.iter().map(day3::item).collect();
+                format!("{} (priority {})", items, priority)
+            };
+            for group in rucksacks
+                .groups(group_size, &mut parse::Parser::new(3, Mode::Lenient))
+                .map_err(|e| e.to_string())?
+            {
+                println!(
+                    "lines {}-{}: {}",
+                    group.line(),
+                    group.line() + group_size - 1,
+                    describe(group.common_items())
+                );
+            }
+            println!("compartments total: {}", rucksacks.part1());
+            println!("groups total: {}", rucksacks.part2());
+            for warning in parser.warnings() {
+                eprintln!("warning: {}", warning);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn day4(command: Day4Command, source: Option<Source>) -> Result<(), String> {
+    let source = source.unwrap_or_default();
+    let manifest = manifest::load_default()?;
+    let input = manifest.load(4, &source)?;
+    let mut parser = parse::Parser::new(4, Mode::Lenient);
+    let assignments = day4::Assignments::parse(&input, &mut parser).map_err(|e| e.to_string())?;
+    let pairs = assignments.pairs();
+    match command {
+        Day4Command::Coverage { from, to } => {
+            let covered = day4::coverage(pairs);
+            let hull = covered.hull().ok_or("there are no assignments")?;
+            let bounds = Interval::new(from.unwrap_or(hull.start()), to.unwrap_or(hull.end()))
+                .ok_or("--from must not be above --to")?;
+            let uncovered = day4::uncovered(pairs, bounds);
+            let shared = day4::multiply_covered(pairs);
+            println!("total coverage: {} sections", covered.coverage());
+            println!(
+                "covered by nobody in {}: {} sections [{}]",
+                bounds,
+                uncovered.coverage(),
+                uncovered
+            );
+            println!(
+                "covered by more than one elf: {} sections [{}]",
+                shared.coverage(),
+                shared
+            );
+        }
+        Day4Command::Render {
+            compress,
+            width,
+            colour,
+            from,
+            to,
+        } => {
+            let hull = day4::coverage(pairs)
+                .hull()
+                .ok_or("there are no assignments")?;
+            let bounds = Interval::new(
+                from.unwrap_or(hull.start().min(1)),
+                to.unwrap_or(hull.end()),
+            )
+            .ok_or("--from must not be above --to")?;
+            let mut renderer = day4::render::Renderer::new(bounds).coloured(colour);
+            if compress {
+                let width = width
+                    .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+                    .unwrap_or(80);
+                let label = pairs
+                    .iter()
+                    .flat_map(|pair| [pair.first(), pair.second()])
+                    .map(|interval| interval.to_string().len())
+                    .max()
+                    .unwrap_or(0);
+                renderer = renderer.compressed(width.saturating_sub(label + 2));
+            }
+            print!(
+                "{}",
+                renderer
+                    .render(pairs)
+                    .map_err(|e| format!("{}; use --compress", e))?
+            );
+        }
+        Day4Command::Sweep { pairs } => {
+            let elves = assignments.elves();
+            let busiest = day4::sweep::busiest_sections(&elves);
+            println!(
+                "most elves on one section: {} at [{}]",
+                busiest.elves(),
+                busiest.sections()
+            );
+            let overlapping = day4::sweep::overlapping_lines(&elves);
+            println!("overlapping pairs of lines: {}", overlapping.len());
+            if pairs {
+                for (first, second) in overlapping {
+                    println!("{} {}", first, second);
+                }
+            }
+        }
+    }
+    for warning in parser.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+    Ok(())
+}
+
+fn day5(command: Day5Command, source: Option<Source>) -> Result<(), String> {
+    match command {
+        Day5Command::Cranes => {
+            for (name, description) in day5::CRANES {
+                println!("{:<16} {}", name, description);
+            }
+        }
+        Day5Command::Run { crane } => {
+            let crane = day5::crane(&crane)?;
+            let source = source.unwrap_or_default();
+            let manifest = manifest::load_default()?;
+            let input = manifest.load(5, &source)?;
+            let mut parser = parse::Parser::new(5, Mode::Lenient);
+            let plan = day5::Plan::parse(&input, &mut parser).map_err(|e| e.to_string())?;
+            let name = crane.name();
+            println!("{}: {}", name, day5::tops(&plan.execute(crane)?));
+            for warning in parser.warnings() {
+                eprintln!("warning: {}", warning);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn new_day(day: u8, title: Option<String>) -> Result<(), String> {
+    if registry::get(day).is_some() {
+        return Err(format!("day {} is already registered", day));
+    }
+    let title = title.unwrap_or_else(|| format!("Day {}", day));
+    for path in scaffold::new_day(&scaffold::src_dir(), day, &title)? {
+        println!("wrote {}", path.display());
+    }
+    println!(
+        "save the puzzle input to {} and rebuild to run day {}; verify skips it until {} lists an answer",
+        input::default_path(day).display(),
+        day,
+        answers::default_path().display()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            strict,
+            format,
+        } => {
+            let mode = if strict { Mode::Strict } else { Mode::Lenient };
+            run(day, part, input, mode, format)
+        }
+        Command::List => {
+            list();
+            Ok(())
+        }
+        Command::Verify { day, answers } => verify(day, answers),
+        Command::Manifest => print_manifest(),
+        Command::Bench {
+            day,
+            runs,
+            input,
+            save,
+            baseline,
+            format,
+        } => run_bench(day, runs, input, save, baseline, format),
+        Command::Day1 { command, input } => day1(command, input),
+        Command::Day2 {
+            command,
+            input,
+            rules,
+        } => day2(command, input, rules),
+        Command::Day3 { command, input } => day3(command, input),
+        Command::Day4 { command, input } => day4(command, input),
+        Command::Day5 { command, input } => day5(command, input),
+        Command::New { day, title } => new_day(day, title),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
 }
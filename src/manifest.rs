@@ -0,0 +1,251 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::input::{self, data_dir, Source};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u8,
+    pub file: String,
+    pub lines: usize,
+    pub checksum: u64,
+}
+
+impl Entry {
+    pub fn for_input(day: u8, file: &str, input: &str) -> Self {
+        Entry {
+            day,
+            file: file.to_string(),
+            lines: input.lines().count(),
+            checksum: checksum(input),
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        data_dir().join(&self.file)
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {:016x}",
+            self.day, self.file, self.lines, self.checksum
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Manifest {
+    entries: Vec<Entry>,
+}
+
+pub fn checksum(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Manifest {
+    pub fn get(&self, day: u8) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.day == day)
+    }
+
+    /// The checked-in input for `day`: the file its entry names, or `dayN` in
+    /// the data directory if the manifest does not list the day.
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.get(day)
+            .map(Entry::path)
+            .unwrap_or_else(|| input::default_path(day))
+    }
+
+    pub fn describe(&self, day: u8, source: &Source) -> String {
+        match source {
+            Source::Default => self.input_path(day).display().to_string(),
+            source => source.to_string(),
+        }
+    }
+
+    /// Loads the input for `day` and checks it against the manifest.
+    pub fn load(&self, day: u8, source: &Source) -> Result<String, String> {
+        let input = input::load_from(day, source, &self.input_path(day))?;
+        self.check(day, source, &input)?;
+        Ok(input)
+    }
+
+    pub fn check(&self, day: u8, source: &Source, input: &str) -> Result<(), String> {
+        if input.trim().is_empty() {
+            return Err(format!(
+                "the input for day {} ({}) is empty",
+                day,
+                self.describe(day, source)
+            ));
+        }
+        let checksum = checksum(input);
+        if let Some(other) = self
+            .entries
+            .iter()
+            .find(|entry| entry.day != day && entry.checksum == checksum)
+        {
+            return Err(format!(
+                "the input for day {} ({}) is the input for day {} ({})",
+                day,
+                self.describe(day, source),
+                other.day,
+                other.file
+            ));
+        }
+        match (source, self.get(day)) {
+            (Source::Default, Some(entry)) => {
+                let lines = input.lines().count();
+                if lines != entry.lines {
+                    Err(format!(
+                        "{} has {} lines but the manifest expects {}",
+                        entry.file, lines, entry.lines
+                    ))
+                } else if checksum != entry.checksum {
+                    Err(format!(
+                        "{} does not match its manifest checksum {:016x}",
+                        entry.file, entry.checksum
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::new();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let entry = match fields[..] {
+                [day, file, lines, checksum] => day.parse().ok().and_then(|day| {
+                    Some(Entry {
+                        day,
+                        file: file.to_string(),
+                        lines: lines.parse().ok()?,
+                        checksum: u64::from_str_radix(checksum, 16).ok()?,
+                    })
+                }),
+                _ => None,
+            };
+            entries.push(entry.ok_or_else(|| {
+                format!(
+                    "manifest line {}: expected 'day file lines checksum', found {:?}",
+                    index + 1,
+                    line
+                )
+            })?);
+        }
+        Ok(Manifest { entries })
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    data_dir().join("manifest")
+}
+
+pub fn load(path: &Path) -> Result<Manifest, String> {
+    fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+        .parse()
+}
+
+#[cfg(feature = "embedded-inputs")]
+fn embedded() -> Option<&'static str> {
+    Some(include_str!("data/manifest"))
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+fn embedded() -> Option<&'static str> {
+    None
+}
+
+/// Loads the checked-in manifest, falling back to the embedded copy like the
+/// inputs it describes.
+pub fn load_default() -> Result<Manifest, String> {
+    match (load(&default_path()), embedded()) {
+        (Ok(manifest), _) => Ok(manifest),
+        (Err(_), Some(manifest)) => manifest.parse(),
+        (Err(e), None) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY1: &str = "1000\n2000\n\n3000\n";
+    const DAY2: &str = "A Y\nB X\nC Z\n";
+
+    fn manifest() -> Manifest {
+        Manifest {
+            entries: vec![
+                Entry::for_input(1, "day1", DAY1),
+                Entry::for_input(2, "day2", DAY2),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = manifest()
+            .entries
+            .iter()
+            .map(Entry::to_string)
+            .collect::<Vec<_>>();
+        let parsed = Manifest::from_str(&text.join("\n")).unwrap();
+        assert_eq!(parsed.entries, manifest().entries);
+    }
+
+    #[test]
+    fn test_check() {
+        let manifest = manifest();
+        assert!(manifest.check(1, &Source::Default, DAY1).is_ok());
+        assert!(manifest.check(2, &Source::Default, "\n\n").is_err());
+        assert!(manifest
+            .check(2, &Source::Default, DAY1)
+            .unwrap_err()
+            .contains("input for day 1"));
+        assert!(manifest
+            .check(2, &Source::Default, "A Y\nB X\n")
+            .unwrap_err()
+            .contains("has 2 lines"));
+        assert!(manifest.check(2, &Source::Stdin, "A Y\nB X\n").is_ok());
+        assert!(manifest.check(3, &Source::Default, "anything").is_ok());
+    }
+
+    #[test]
+    fn test_checked_in_inputs() {
+        let manifest = load_default().unwrap();
+        for entry in &manifest.entries {
+            let input = fs::read_to_string(entry.path()).unwrap();
+            assert_eq!(manifest.load(entry.day, &Source::Default), Ok(input));
+        }
+    }
+
+    #[test]
+    fn test_load_from_entry_file() {
+        let day1 = fs::read_to_string(input::default_path(1)).unwrap();
+        let manifest = Manifest {
+            entries: vec![Entry::for_input(9, "day1", &day1)],
+        };
+        assert_eq!(manifest.input_path(9), data_dir().join("day1"));
+        assert_eq!(manifest.load(9, &Source::Default), Ok(day1));
+        assert_eq!(manifest.input_path(10), input::default_path(10));
+        assert!(manifest.load(10, &Source::Default).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new<T: Into<String>, E: Into<String>>(column: usize, text: T, expected: E) -> Self {
+        ParseError {
+            day: 0,
+            line: 0,
+            column,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    pub fn shifted(mut self, columns: usize) -> Self {
+        self.column += columns;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}, found {:?}",
+            self.day, self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Lenient,
+    Strict,
+}
+
+#[derive(Debug)]
+pub struct Parser {
+    day: u8,
+    mode: Mode,
+    warnings: Vec<ParseError>,
+}
+
+impl Parser {
+    pub fn new(day: u8, mode: Mode) -> Self {
+        Parser {
+            day,
+            mode,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn strict(day: u8) -> Self {
+        Parser::new(day, Mode::Strict)
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn fail(&self, mut error: ParseError) -> ParseError {
+        error.day = self.day;
+        error
+    }
+
+    pub fn skip(&mut self, error: ParseError) -> Result<(), ParseError> {
+        let error = self.fail(error);
+        match self.mode {
+            Mode::Strict => Err(error),
+            Mode::Lenient => {
+                self.warnings.push(error);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn lines<T: FromStr<Err = ParseError>>(
+        &mut self,
+        input: &str,
+    ) -> Result<Vec<T>, ParseError> {
+        self.lines_from(input, 1)
+    }
+
+    pub fn lines_from<T: FromStr<Err = ParseError>>(
+        &mut self,
+        input: &str,
+        first_line: usize,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut parsed = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            match T::from_str(line) {
+                Ok(value) => parsed.push(value),
+                Err(e) => self.skip(e.at_line(first_line + index))?,
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn warnings(&self) -> &[ParseError] {
+        &self.warnings
+    }
+
+    pub fn into_warnings(self) -> Vec<ParseError> {
+        self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Digit(u32);
+
+    impl FromStr for Digit {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse()
+                .map(Digit)
+                .map_err(|_| ParseError::new(1, s, "a number"))
+        }
+    }
+
+    const INPUT: &str = "1
+x
+3";
+
+    #[test]
+    fn test_lenient_skips_bad_lines() {
+        let mut parser = Parser::new(4, Mode::Lenient);
+        let digits: Vec<Digit> = parser.lines(INPUT).unwrap();
+        assert_eq!(digits.iter().map(|d| d.0).sum::<u32>(), 4);
+        assert_eq!(
+            parser.warnings(),
+            &[ParseError {
+                day: 4,
+                line: 2,
+                column: 1,
+                text: "x".to_string(),
+                expected: "a number".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_strict_fails_on_first_bad_line() {
+        let error = Parser::strict(4).lines::<Digit>(INPUT).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "day 4, line 2, column 1: expected a number, found \"x\""
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::parse::{Mode, ParseError, Parser};
+use crate::solution::Solution;
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("unknown part {}, expected 1 or 2", s)),
+        }
+    }
+}
+
+pub struct Day {
+    number: u8,
+    title: &'static str,
+    solve: fn(&str, &[Part], Mode) -> Result<Solved, ParseError>,
+}
+
+pub struct Answer {
+    part: Part,
+    value: String,
+    elapsed: Duration,
+}
+
+impl Answer {
+    pub fn part(&self) -> Part {
+        self.part
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+pub struct Solved {
+    parse_elapsed: Duration,
+    answers: Vec<Answer>,
+    warnings: Vec<ParseError>,
+}
+
+impl Solved {
+    pub fn parse_elapsed(&self) -> Duration {
+        self.parse_elapsed
+    }
+
+    pub fn answers(&self) -> &[Answer] {
+        &self.answers
+    }
+
+    /// Lines skipped in lenient mode.
+    pub fn warnings(&self) -> &[ParseError] {
+        &self.warnings
+    }
+}
+
+impl Day {
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    pub fn title(&self) -> &'static str {
+        self.title
+    }
+
+    pub fn solve(&self, input: &str, parts: &[Part], mode: Mode) -> Result<Solved, ParseError> {
+        (self.solve)(input, parts, mode)
+    }
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part], mode: Mode) -> Result<Solved, ParseError> {
+    let mut parser = Parser::new(S::DAY, mode);
+    let start = Instant::now();
+    let solution = S::parse(input, &mut parser)?;
+    let parse_elapsed = start.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let value = match part {
+                Part::One => solution.part1().to_string(),
+                Part::Two => solution.part2().to_string(),
+            };
+            Answer {
+                part,
+                value,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+    Ok(Solved {
+        parse_elapsed,
+        answers,
+        warnings: parser.into_warnings(),
+    })
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        title: "Calorie Counting",
+        solve: solve::<day1::Inventory>,
+    },
+    Day {
+        number: 2,
+        title: "Rock Paper Scissors",
+        solve: solve::<day2::Guide>,
+    },
+    Day {
+        number: 3,
+        title: "Rucksack Reorganization",
+        solve: solve::<day3::Rucksacks>,
+    },
+    Day {
+        number: 4,
+        title: "Camp Cleanup",
+        solve: solve::<day4::Assignments>,
+    },
+    Day {
+        number: 5,
+        title: "Supply Stacks",
+        solve: solve::<day5::Plan>,
+    },
+    Day {
+        number: 6,
+        title: "Tuning Trouble",
+        solve: solve::<day6::Datastream>,
+    },
+    Day {
+        number: 7,
+        title: "No Space Left On Device",
+        solve: solve::<day7::Shell>,
+    },
+    Day {
+        number: 8,
+        title: "Treetop Tree House",
+        solve: solve::<day8::Map>,
+    },
+];
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Selection {
+    All,
+    Day(u8),
+}
+
+impl Selection {
+    pub fn days(&self) -> Result<Vec<&'static Day>, String> {
+        match self {
+            Selection::All => Ok(DAYS.iter().collect()),
+            Selection::Day(number) => get(*number)
+                .map(|day| vec![day])
+                .ok_or_else(|| format!("day {} is not implemented", number)),
+        }
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Selection::All),
+            _ => s
+                .parse()
+                .map(Selection::Day)
+                .map_err(|_| format!("expected a day number or 'all', got {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_sorted_and_unique() {
+        assert!(DAYS.windows(2).all(|w| w[0].number < w[1].number));
+    }
+
+    #[test]
+    fn test_selection() {
+        assert_eq!(Selection::from_str("all"), Ok(Selection::All));
+        assert_eq!(Selection::from_str("5"), Ok(Selection::Day(5)));
+        assert!(Selection::from_str("five").is_err());
+        assert!(Selection::Day(26).days().is_err());
+        assert_eq!(Selection::All.days().unwrap().len(), DAYS.len());
+    }
+}
@@ -0,0 +1,304 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn src_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+pub fn module_source(day: u8) -> String {
+    format!(
+        r#"use crate::parse::{{ParseError, Parser}};
+use crate::solution::Solution;
+
+pub struct Puzzle {{
+    lines: Vec<String>,
+}}
+
+impl Solution for Puzzle {{
+    const DAY: u8 = {day};
+
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str, _parser: &mut Parser) -> Result<Self, ParseError> {{
+        Ok(Puzzle {{
+            lines: input.lines().map(str::to_string).collect(),
+        }})
+    }}
+
+    fn part1(&self) -> usize {{
+        self.lines.len()
+    }}
+
+    fn part2(&self) -> usize {{
+        self.lines.len()
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn test_part1() {{
+        let puzzle = Puzzle::parse(EXAMPLE, &mut Parser::strict({day})).unwrap();
+        assert_eq!(0, puzzle.part1());
+    }}
+
+    #[test]
+    fn test_part2() {{
+        let puzzle = Puzzle::parse(EXAMPLE, &mut Parser::strict({day})).unwrap();
+        assert_eq!(0, puzzle.part2());
+    }}
+}}
+"#
+    )
+}
+
+fn insert_after_last_line(
+    source: &str,
+    matches: impl Fn(&str) -> bool,
+    new_line: &str,
+) -> Result<String, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let index = lines
+        .iter()
+        .rposition(|line| matches(line))
+        .ok_or_else(|| format!("cannot find where to insert {:?}", new_line.trim()))?;
+    let mut updated = lines[..=index].to_vec();
+    updated.push(new_line);
+    updated.extend(&lines[index + 1..]);
+    Ok(updated.join("\n") + "\n")
+}
+
+fn is_day_line(line: &str, prefix: &str, suffix: &str) -> bool {
+    line.trim()
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(suffix))
+        .is_some_and(|number| number.parse::<u8>().is_ok())
+}
+
+pub fn register_module(lib: &str, day: u8) -> Result<String, String> {
+    insert_after_last_line(
+        lib,
+        |line| is_day_line(line, "pub mod day", ";"),
+        &format!("pub mod day{};", day),
+    )
+}
+
+pub fn register_embedded(input: &str, day: u8) -> Result<String, String> {
+    insert_after_last_line(
+        input,
+        |line| {
+            line.trim_start()
+                .contains(" => Some(include_str!(\"data/day")
+        },
+        &format!(
+            "        {} => Some(include_str!(\"data/day{}\")),",
+            day, day
+        ),
+    )
+}
+
+pub fn register_day(registry: &str, day: u8, title: &str) -> Result<String, String> {
+    let start = registry
+        .find("use crate::{day")
+        .ok_or("cannot find the day imports in the registry")?;
+    let end = start
+        + registry[start..]
+            .find("};")
+            .ok_or("cannot find the end of the day imports in the registry")?;
+    let mut modules: Vec<String> = registry[start + "use crate::{".len()..end]
+        .split(',')
+        .map(|module| module.trim().to_string())
+        .filter(|module| !module.is_empty())
+        .collect();
+    modules.push(format!("day{}", day));
+    modules.sort_by_key(|module| module["day".len()..].parse::<u8>().unwrap_or(u8::MAX));
+    let imports = format!("use crate::{{{}}}", modules.join(", "));
+    let registry = format!("{}{}{}", &registry[..start], imports, &registry[end + 1..]);
+
+    let table = registry
+        .find("pub const DAYS")
+        .ok_or("cannot find DAYS in the registry")?;
+    let close = table
+        + registry[table..]
+            .find("\n];")
+            .ok_or("cannot find the end of DAYS in the registry")?;
+    let entry = format!(
+        "\n    Day {{\n        number: {},\n        title: {:?},\n        solve: solve::<day{}::Puzzle>,\n    }},",
+        day, title, day
+    );
+    Ok(format!(
+        "{}{}{}",
+        &registry[..close],
+        entry,
+        &registry[close..]
+    ))
+}
+
+/// Where a file is written before it is renamed over `path`.
+fn staged(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.new", name))
+}
+
+/// Writes every file next to its target and only renames them into place
+/// once all of them were written, so a failed write leaves the tree as it was.
+fn write_all(files: Vec<(PathBuf, String)>) -> Result<Vec<PathBuf>, String> {
+    for (index, (path, contents)) in files.iter().enumerate() {
+        if let Err(e) = fs::write(staged(path), contents) {
+            for (path, _) in &files[..=index] {
+                let _ = fs::remove_file(staged(path));
+            }
+            return Err(format!("cannot write {}: {}", path.display(), e));
+        }
+    }
+    let mut written = Vec::new();
+    for (path, _) in files {
+        fs::rename(staged(&path), &path)
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+pub fn new_day(src: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>, String> {
+    let module = src.join(format!("day{}.rs", day));
+    let data = src.join("data").join(format!("day{}", day));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()));
+    }
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))
+    };
+    let lib = src.join("lib.rs");
+    let registry = src.join("registry.rs");
+    let input = src.join("input.rs");
+    let mut files = vec![
+        (lib.clone(), register_module(&read(&lib)?, day)?),
+        (
+            registry.clone(),
+            register_day(&read(&registry)?, day, title)?,
+        ),
+        (input.clone(), register_embedded(&read(&input)?, day)?),
+        (module, module_source(day)),
+    ];
+    if !data.exists() {
+        files.push((data, String::new()));
+    }
+    write_all(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_module() {
+        let lib = "pub mod bench;\npub mod day1;\npub mod day2;\npub mod input;\n";
+        assert_eq!(
+            register_module(lib, 3).unwrap(),
+            "pub mod bench;\npub mod day1;\npub mod day2;\npub mod day3;\npub mod input;\n"
+        );
+    }
+
+    #[test]
+    fn test_register_day() {
+        let registry = "use crate::{day1, day2};
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        title: \"Calorie Counting\",
+        solve: solve::<day1::Inventory>,
+    },
+];
+";
+        assert_eq!(
+            register_day(registry, 10, "Cathode-Ray Tube").unwrap(),
+            "use crate::{day1, day2, day10};
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        title: \"Calorie Counting\",
+        solve: solve::<day1::Inventory>,
+    },
+    Day {
+        number: 10,
+        title: \"Cathode-Ray Tube\",
+        solve: solve::<day10::Puzzle>,
+    },
+];
+"
+        );
+    }
+
+    #[test]
+    fn test_register_embedded() {
+        let input = "    match day {
+        1 => Some(include_str!(\"data/day1\")),
+        _ => None,
+";
+        assert_eq!(
+            register_embedded(input, 2).unwrap(),
+            "    match day {
+        1 => Some(include_str!(\"data/day1\")),
+        2 => Some(include_str!(\"data/day2\")),
+        _ => None,
+"
+        );
+    }
+
+    #[test]
+    fn test_current_tree_can_be_extended() {
+        let read = |file: &str| fs::read_to_string(src_dir().join(file)).unwrap();
+        assert!(register_module(&read("lib.rs"), 25).is_ok());
+        assert!(register_day(&read("registry.rs"), 25, "Day 25").is_ok());
+        assert!(register_embedded(&read("input.rs"), 25).is_ok());
+    }
+
+    fn scratch_tree(name: &str) -> PathBuf {
+        let src = std::env::temp_dir().join(format!("aoc2022-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&src);
+        fs::create_dir_all(&src).unwrap();
+        for file in ["lib.rs", "registry.rs", "input.rs"] {
+            fs::copy(src_dir().join(file), src.join(file)).unwrap();
+        }
+        src
+    }
+
+    #[test]
+    fn test_new_day() {
+        let src = scratch_tree("new-day");
+        fs::create_dir(src.join("data")).unwrap();
+        let written = new_day(&src, 25, "Full of Hot Air").unwrap();
+        assert_eq!(written.len(), 5);
+        assert!(fs::read_to_string(src.join("lib.rs"))
+            .unwrap()
+            .contains("pub mod day25;"));
+        assert!(src.join("day25.rs").exists());
+        assert!(src.join("data").join("day25").exists());
+        assert!(new_day(&src, 25, "Full of Hot Air").is_err());
+        fs::remove_dir_all(&src).unwrap();
+    }
+
+    #[test]
+    fn test_new_day_writes_nothing_on_failure() {
+        let src = scratch_tree("failed-day");
+        let before = fs::read_to_string(src.join("lib.rs")).unwrap();
+        // Without a data directory the last file cannot be written.
+        assert!(new_day(&src, 25, "Full of Hot Air").is_err());
+        assert_eq!(fs::read_to_string(src.join("lib.rs")).unwrap(), before);
+        let mut left = fs::read_dir(&src)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        left.sort();
+        assert_eq!(left, ["input.rs", "lib.rs", "registry.rs"]);
+        fs::remove_dir_all(&src).unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+use crate::parse::{ParseError, Parser};
+use std::fmt::Display;
+
+pub trait Solution: Sized {
+    const DAY: u8;
+
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str, parser: &mut Parser) -> Result<Self, ParseError>;
+    fn part1(&self) -> Self::Part1;
+    fn part2(&self) -> Self::Part2;
+}
@@ -0,0 +1,30 @@
+use aoc2022::day5::{crate_mover_9001, tops, Plan};
+use aoc2022::day7::Shell;
+use aoc2022::parse::{Mode, Parser};
+use aoc2022::registry::{self, Part};
+use aoc2022::solution::Solution;
+use std::str::FromStr;
+
+#[test]
+fn test_registry_solves_examples() {
+    let day = registry::get(4).unwrap();
+    let solved = day
+        .solve(
+            "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8",
+            &Part::ALL,
+            Mode::Strict,
+        )
+        .unwrap();
+    let answers: Vec<_> = solved.answers().iter().map(|a| a.value()).collect();
+    assert_eq!(answers, ["2", "4"]);
+}
+
+#[test]
+fn test_model_types_are_usable() {
+    let plan = Plan::from_str("[A]\n 1 \n\nmove 1 from 1 to 1").unwrap();
+    assert_eq!(tops(&plan.execute(crate_mover_9001).unwrap()), "A");
+
+    let shell = Shell::parse("$ cd /\n$ ls\n100 a\ndir b", &mut Parser::strict(7)).unwrap();
+    assert_eq!(shell.fs().size(), 100);
+    assert_eq!(shell.fs().walk().count(), 2);
+}